@@ -15,7 +15,7 @@ use crate::mssql;
 
 #[cfg(feature = "mysql")]
 use crate::mysql;
-use crate::transaction::Transaction;
+use crate::transaction::{Transaction, TransactionOptions};
 
 mod establish;
 mod executor;
@@ -163,6 +163,16 @@ impl Connection for AnyConnection {
         Transaction::begin(self)
     }
 
+    fn begin_with(
+        &mut self,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
+    where
+        Self: Sized,
+    {
+        Transaction::begin_with(self, options)
+    }
+
     fn cached_statements_size(&self) -> usize {
         match &self.0 {
             #[cfg(feature = "postgres")]
@@ -21,7 +21,7 @@ impl MigrateDatabase for Any {
                 AnyKind::MySql => crate::mysql::MySql::create_database(url).await,
 
                 #[cfg(feature = "mssql")]
-                AnyKind::Mssql => unimplemented!(),
+                AnyKind::Mssql => crate::mssql::Mssql::create_database(url).await,
             }
         })
     }
@@ -39,7 +39,7 @@ impl MigrateDatabase for Any {
                 AnyKind::MySql => crate::mysql::MySql::database_exists(url).await,
 
                 #[cfg(feature = "mssql")]
-                AnyKind::Mssql => unimplemented!(),
+                AnyKind::Mssql => crate::mssql::Mssql::database_exists(url).await,
             }
         })
     }
@@ -57,7 +57,7 @@ impl MigrateDatabase for Any {
                 AnyKind::MySql => crate::mysql::MySql::drop_database(url).await,
 
                 #[cfg(feature = "mssql")]
-                AnyKind::Mssql => unimplemented!(),
+                AnyKind::Mssql => crate::mssql::Mssql::drop_database(url).await,
             }
         })
     }
@@ -76,7 +76,7 @@ impl Migrate for AnyConnection {
             AnyConnectionKind::MySql(conn) => conn.ensure_migrations_table(),
 
             #[cfg(feature = "mssql")]
-            AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+            AnyConnectionKind::Mssql(conn) => conn.ensure_migrations_table(),
         }
     }
 
@@ -93,7 +93,7 @@ impl Migrate for AnyConnection {
             AnyConnectionKind::MySql(conn) => conn.version(),
 
             #[cfg(feature = "mssql")]
-            AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+            AnyConnectionKind::Mssql(conn) => conn.version(),
         }
     }
 
@@ -109,7 +109,7 @@ impl Migrate for AnyConnection {
             AnyConnectionKind::MySql(conn) => conn.dirty_version(),
 
             #[cfg(feature = "mssql")]
-            AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+            AnyConnectionKind::Mssql(conn) => conn.dirty_version(),
         }
     }
 
@@ -129,10 +129,7 @@ impl Migrate for AnyConnection {
             AnyConnectionKind::MySql(conn) => conn.validate(migration),
 
             #[cfg(feature = "mssql")]
-            AnyConnectionKind::Mssql(_conn) => {
-                let _ = migration;
-                unimplemented!()
-            }
+            AnyConnectionKind::Mssql(conn) => conn.validate(migration),
         }
     }
 
@@ -150,7 +147,7 @@ impl Migrate for AnyConnection {
             AnyConnectionKind::MySql(conn) => conn.list_applied_migrations(),
 
             #[cfg(feature = "mssql")]
-            AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+            AnyConnectionKind::Mssql(conn) => conn.list_applied_migrations(),
         }
     }
 
@@ -166,7 +163,7 @@ impl Migrate for AnyConnection {
             AnyConnectionKind::MySql(conn) => conn.lock(),
 
             #[cfg(feature = "mssql")]
-            AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+            AnyConnectionKind::Mssql(conn) => conn.lock(),
         }
     }
 
@@ -182,7 +179,7 @@ impl Migrate for AnyConnection {
             AnyConnectionKind::MySql(conn) => conn.unlock(),
 
             #[cfg(feature = "mssql")]
-            AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+            AnyConnectionKind::Mssql(conn) => conn.unlock(),
         }
     }
 
@@ -201,10 +198,7 @@ impl Migrate for AnyConnection {
             AnyConnectionKind::MySql(conn) => conn.apply(migration),
 
             #[cfg(feature = "mssql")]
-            AnyConnectionKind::Mssql(_conn) => {
-                let _ = migration;
-                unimplemented!()
-            }
+            AnyConnectionKind::Mssql(conn) => conn.apply(migration),
         }
     }
 
@@ -223,10 +217,7 @@ impl Migrate for AnyConnection {
             AnyConnectionKind::MySql(conn) => conn.revert(migration),
 
             #[cfg(feature = "mssql")]
-            AnyConnectionKind::Mssql(_conn) => {
-                let _ = migration;
-                unimplemented!()
-            }
+            AnyConnectionKind::Mssql(conn) => conn.revert(migration),
         }
     }
 }
@@ -1,6 +1,6 @@
 use crate::database::{Database, HasStatementCache};
 use crate::error::Error;
-use crate::transaction::Transaction;
+use crate::transaction::{unsupported, RetryPolicy, Transaction, TransactionOptions};
 use futures_core::future::BoxFuture;
 use log::LevelFilter;
 use std::fmt::Debug;
@@ -36,6 +36,27 @@ pub trait Connection: Send {
     where
         Self: Sized;
 
+    /// Begin a new transaction with the given isolation level and access mode.
+    ///
+    /// Returns an error if a transaction is already active on this connection and the options
+    /// are not the defaults, as they cannot be applied to a savepoint.
+    ///
+    /// See [`TransactionOptions`] for how each database applies the options. The default
+    /// implementation only supports the default options.
+    fn begin_with(
+        &mut self,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
+    where
+        Self: Sized,
+    {
+        if options.is_default() {
+            self.begin()
+        } else {
+            Box::pin(async move { Err(unsupported("transaction options")) })
+        }
+    }
+
     /// Execute the function inside a transaction.
     ///
     /// If the function returns an error, the transaction will be rolled back. If it does not
@@ -85,6 +106,108 @@ pub trait Connection: Send {
         })
     }
 
+    /// Execute the function inside a transaction started with the given options, running it
+    /// again when the transaction fails because of a conflict with a concurrent transaction.
+    ///
+    /// The transaction is committed if the function does not return an error, and rolled back
+    /// otherwise. If the function or the commit fails with an error for which
+    /// [`DatabaseError::is_retryable`] returns `true`, such as a serialization failure or a
+    /// deadlock, the function is called again in a new transaction after the backoff of the
+    /// [`RetryPolicy`], until it succeeds or the attempts run out.
+    ///
+    /// As the function may be called several times, it should not have side effects outside of
+    /// the transaction.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sqlx_core::connection::Connection;
+    /// use sqlx_core::error::Error;
+    /// use sqlx_core::postgres::PgConnection;
+    /// use sqlx_core::query::query;
+    /// use sqlx_core::transaction::{IsolationLevel, RetryPolicy, TransactionOptions};
+    ///
+    /// # pub async fn _f(conn: &mut PgConnection) -> Result<(), Error> {
+    /// let options = TransactionOptions::new().isolation_level(IsolationLevel::Serializable);
+    ///
+    /// conn.transaction_with_retry(options, RetryPolicy::new(), |conn| Box::pin(async move {
+    ///     query("update accounts set balance = balance - 10 where id = 1")
+    ///         .execute(&mut *conn)
+    ///         .await?;
+    ///
+    ///     query("update accounts set balance = balance + 10 where id = 2")
+    ///         .execute(&mut *conn)
+    ///         .await?;
+    ///
+    ///     Ok(())
+    /// })).await
+    /// # }
+    /// ```
+    ///
+    /// [`DatabaseError::is_retryable`]: crate::error::DatabaseError::is_retryable()
+    fn transaction_with_retry<'a, F, R>(
+        &'a mut self,
+        options: TransactionOptions,
+        policy: RetryPolicy,
+        mut callback: F,
+    ) -> BoxFuture<'a, Result<R, Error>>
+    where
+        for<'c> F: FnMut(&'c mut Transaction<'_, Self::Database>) -> BoxFuture<'c, Result<R, Error>>
+            + 'a
+            + Send
+            + Sync,
+        Self: Sized,
+        R: Send,
+    {
+        Box::pin(async move {
+            let mut attempt = 1;
+
+            loop {
+                let ret = async {
+                    let mut transaction = self.begin_with(options).await?;
+
+                    match callback(&mut transaction).await {
+                        Ok(ret) => {
+                            transaction.commit().await?;
+
+                            Ok(ret)
+                        }
+                        Err(err) => {
+                            // the error of the callback decides whether to retry
+                            if let Err(rollback_err) = transaction.rollback().await {
+                                log::warn!(
+                                    "failed to roll back transaction after error {}: {}",
+                                    err,
+                                    rollback_err
+                                );
+                            }
+
+                            Err(err)
+                        }
+                    }
+                }
+                .await;
+
+                match ret {
+                    Err(Error::Database(err))
+                        if err.is_retryable() && attempt < policy.max_attempts =>
+                    {
+                        log::debug!(
+                            "retrying transaction after attempt {} failed: {}",
+                            attempt,
+                            err
+                        );
+
+                        sqlx_rt::sleep(policy.backoff(attempt)).await;
+                        attempt += 1;
+                    }
+
+                    ret => return ret,
+                }
+            }
+        })
+    }
+
     /// The number of statements currently cached in the connection.
     fn cached_statements_size(&self) -> usize
     where
@@ -173,6 +173,15 @@ pub trait DatabaseError: 'static + Send + Sync + StdError {
         false
     }
 
+    /// Returns `true` if the transaction failed because of a conflict with a concurrent
+    /// transaction, such as a serialization failure or a deadlock, and running it again from
+    /// the start may succeed.
+    ///
+    /// See [`Connection::transaction_with_retry`](crate::connection::Connection::transaction_with_retry).
+    fn is_retryable(&self) -> bool {
+        false
+    }
+
     /// Returns the name of the constraint that triggered the error, if applicable.
     /// If the error was caused by a conflict of a unique index, this will be the index name.
     ///
@@ -181,6 +190,57 @@ pub trait DatabaseError: 'static + Send + Sync + StdError {
     fn constraint(&self) -> Option<&str> {
         None
     }
+
+    /// Returns the name of the table the error relates to, if applicable.
+    ///
+    /// ### Note
+    /// Currently only populated by the Postgres driver, the other drivers return `None`.
+    fn table(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns the name of the column the error relates to, if applicable.
+    ///
+    /// ### Note
+    /// Currently only populated by the Postgres driver, the other drivers return `None`.
+    fn column(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns the category of the error, so it can be handled without matching the error
+    /// codes of each database.
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+/// The category of a [`DatabaseError`], see [`DatabaseError::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A unique or primary key constraint was violated.
+    UniqueViolation,
+
+    /// A foreign key constraint was violated.
+    ForeignKeyViolation,
+
+    /// A `NULL` was written to a `NOT NULL` column.
+    NotNullViolation,
+
+    /// A check constraint was violated.
+    CheckViolation,
+
+    /// The transaction was aborted to resolve a deadlock with another transaction.
+    Deadlock,
+
+    /// The transaction was aborted because it conflicts with a concurrent transaction.
+    SerializationFailure,
+
+    /// A statement timed out, or a lock could not be acquired in time.
+    Timeout,
+
+    /// Any other error.
+    Other,
 }
 
 impl dyn DatabaseError {
@@ -1,9 +1,11 @@
 use crate::arguments::Arguments;
-use crate::encode::Encode;
+use crate::encode::{Encode, IsNull};
 use crate::mssql::database::Mssql;
 use crate::mssql::io::MssqlBufMutExt;
 use crate::mssql::protocol::rpc::StatusFlags;
+use crate::mssql::protocol::type_info::{Collation, DataType, TypeInfo};
 use crate::types::Type;
+use std::fmt::{self, Write};
 
 #[derive(Default, Clone)]
 pub struct MssqlArguments {
@@ -13,6 +15,17 @@ pub struct MssqlArguments {
     name: String,
     pub(crate) data: Vec<u8>,
     pub(crate) declarations: String,
+    // `varchar` parameters to encode with the collation of the database
+    varchars: Vec<VarCharParam>,
+}
+
+#[derive(Clone)]
+struct VarCharParam {
+    // where the TYPE_INFO of the parameter starts and its value ends in `data`
+    start: usize,
+    end: usize,
+    ty: TypeInfo,
+    value: Option<String>,
 }
 
 impl MssqlArguments {
@@ -29,12 +42,8 @@ impl MssqlArguments {
         self.data.put_b_varchar(name); // [ParamName]
         self.data.push(0); // [StatusFlags]
 
-        // let offset = self.data.len();
-        ty.0.put(&mut self.data); // [TYPE_INFO]       
-        ty.0.put_value(&mut self.data, value); // [ParamLenData]
-        // if name == "@P1"{
-        //     println!("field data:{:?}", &self.data[offset..self.data.len()])
-        // }
+        // [TYPE_INFO] [ParamLenData]
+        put_param(&mut self.data, &mut self.varchars, ty.0, value);
     }
 
     pub(crate) fn add_unnamed<'q, T: Encode<'q, Mssql> + Type<Mssql>>(&mut self, value: T) {
@@ -48,19 +57,80 @@ impl MssqlArguments {
     ) {
         let ty = initial_value.produces().unwrap_or_else(T::type_info);
 
-        let mut ty_name = String::new();
-        ty.0.fmt(&mut ty_name);
+        self.data.put_b_varchar(name); // [ParamName]
+        self.data.push(StatusFlags::BY_REF_VALUE.bits()); // [StatusFlags]
+
+        // [TYPE_INFO] [ParamLenData]
+        put_param(
+            &mut self.data,
+            &mut self.varchars,
+            output_type(ty.0),
+            initial_value,
+        );
+    }
+
+    // declares an OUTPUT parameter that is initially NULL
+    pub(crate) fn declare_output<'q, T: 'q + Encode<'q, Mssql> + Type<Mssql>>(
+        &mut self,
+        name: &str,
+    ) {
+        // NOTE: we cannot use `produces()` here as a `None` would produce the `NULL` type
+        //       and the server would not know what type of value to return
+        let ty = T::type_info();
 
         self.data.put_b_varchar(name); // [ParamName]
         self.data.push(StatusFlags::BY_REF_VALUE.bits()); // [StatusFlags]
 
-        ty.0.put(&mut self.data); // [TYPE_INFO]
-        ty.0.put_value(&mut self.data, initial_value); // [ParamLenData]
+        // [TYPE_INFO] [ParamLenData]
+        put_param(
+            &mut self.data,
+            &mut self.varchars,
+            output_type(ty.0),
+            Option::<T>::None,
+        );
     }
 
     pub(crate) fn append(&mut self, arguments: &mut MssqlArguments) {
+        for param in &mut arguments.varchars {
+            param.start += self.data.len();
+            param.end += self.data.len();
+        }
+
         self.ordinal += arguments.ordinal;
         self.data.append(&mut arguments.data);
+        self.varchars.append(&mut arguments.varchars);
+    }
+
+    // encodes the `varchar` parameters with the collation of the database
+    pub(crate) fn apply_collation(&mut self, collation: Collation) {
+        if self
+            .varchars
+            .iter()
+            .all(|param| param.ty.collation == Some(collation))
+        {
+            return;
+        }
+
+        let mut data = Vec::with_capacity(self.data.len());
+        let mut offset = 0;
+
+        for param in &mut self.varchars {
+            data.extend_from_slice(&self.data[offset..param.start]);
+            offset = param.end;
+
+            param.ty.collation = Some(collation);
+            param.start = data.len();
+
+            param.ty.put(&mut data);
+            param
+                .ty
+                .put_value(&mut data, param.value.as_deref().map(str::as_bytes));
+
+            param.end = data.len();
+        }
+
+        data.extend_from_slice(&self.data[offset..]);
+        self.data = data;
     }
 
     pub(crate) fn add<'q, T>(&mut self, value: T)
@@ -82,6 +152,7 @@ impl MssqlArguments {
             ref name,
             ref mut declarations,
             ref mut data,
+            ref mut varchars,
             ..
         } = self;
 
@@ -100,11 +171,79 @@ impl MssqlArguments {
         data.put_b_varchar(name); // [ParamName]
         data.push(0); // [StatusFlags]
 
-        ty.0.put(data); // [TYPE_INFO]
-        ty.0.put_value(data, value); // [ParamLenData]
+        // [TYPE_INFO] [ParamLenData]
+        put_param(data, varchars, ty.0, value);
     }
 }
 
+// The value returned in an OUTPUT parameter may be longer than its initial value, so strings
+// and binary values are declared as `nvarchar(4000)`, `varchar(8000)` or `varbinary(8000)`,
+// unless they are already longer than that and sent as (MAX).
+fn output_type(mut ty: TypeInfo) -> TypeInfo {
+    match ty.ty {
+        DataType::NVarChar | DataType::NChar if ty.size <= 4000 => {
+            ty.ty = DataType::NVarChar;
+            ty.size = 4000;
+
+            if ty.collation.is_none() {
+                ty.collation =
+                    <&str as Encode<'_, Mssql>>::produces(&"").and_then(|ty| ty.0.collation);
+            }
+        }
+
+        DataType::BigVarChar | DataType::BigChar if ty.size <= 8000 => {
+            ty.ty = DataType::BigVarChar;
+            ty.size = 8000;
+        }
+
+        DataType::BigVarBinary | DataType::BigBinary if ty.size <= 8000 => {
+            ty.ty = DataType::BigVarBinary;
+            ty.size = 8000;
+        }
+
+        _ => {}
+    }
+
+    ty
+}
+
+fn put_param<'q, T: Encode<'q, Mssql>>(
+    data: &mut Vec<u8>,
+    varchars: &mut Vec<VarCharParam>,
+    mut ty: TypeInfo,
+    value: T,
+) {
+    if ty.collation.is_some() || !matches!(ty.ty, DataType::BigVarChar | DataType::BigChar) {
+        ty.put(data);
+        ty.put_value(data, value);
+
+        return;
+    }
+
+    // the collation of the database is only known when the parameter is sent; until then,
+    // the value is encoded with the default collation
+    let mut text = Vec::new();
+
+    let value = match value.encode(&mut text) {
+        IsNull::Yes => None,
+        IsNull::No => Some(String::from_utf8_lossy(&text).into_owned()),
+    };
+
+    ty.collation = <&str as Encode<'_, Mssql>>::produces(&"").and_then(|ty| ty.0.collation);
+
+    let start = data.len();
+
+    ty.put(data);
+    ty.put_value(data, value.as_deref().map(str::as_bytes));
+
+    varchars.push(VarCharParam {
+        start,
+        end: data.len(),
+        ty,
+        value,
+    });
+}
+
 impl<'q> Arguments<'q> for MssqlArguments {
     type Database = Mssql;
 
@@ -118,4 +257,9 @@ impl<'q> Arguments<'q> for MssqlArguments {
     {
         self.add(value)
     }
+
+    fn format_placeholder<W: Write>(&self, writer: &mut W) -> fmt::Result {
+        // the name of the most recently added parameter
+        write!(writer, "@P{}", self.ordinal)
+    }
 }
@@ -0,0 +1,122 @@
+use std::io;
+use std::time::Duration;
+
+use sqlx_rt::{timeout, UdpSocket};
+
+use crate::error::Error;
+
+// The SQL Server Browser service answers on this UDP port
+// <https://docs.microsoft.com/en-us/openspecs/windows_protocols/mc-sqlr>
+const BROWSER_PORT: u16 = 1434;
+
+// How long to wait for the SQL Server Browser to answer
+const BROWSER_TIMEOUT: Duration = Duration::from_secs(2);
+
+// A request for the information of a single instance (CLNT_UCAST_INST)
+const CLNT_UCAST_INST: u8 = 0x04;
+
+// The response of the server (SVR_RESP)
+const SVR_RESP: u8 = 0x05;
+
+// Ask the SQL Server Browser service on `host` for the TCP port of the named instance.
+pub(crate) async fn resolve_instance_port(host: &str, instance: &str) -> Result<u16, Error> {
+    let socket = UdpSocket::bind(("0.0.0.0", 0)).await?;
+    socket.connect((host, BROWSER_PORT)).await?;
+
+    let mut request = Vec::with_capacity(instance.len() + 2);
+    request.push(CLNT_UCAST_INST);
+    request.extend_from_slice(instance.as_bytes());
+    request.push(0);
+
+    socket.send(&request).await?;
+
+    // the response to CLNT_UCAST_INST is at most 1024 bytes of data, after its header
+    let mut buf = [0_u8; 1027];
+
+    let len = timeout(BROWSER_TIMEOUT, socket.recv(&mut buf))
+        .await
+        .map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::TimedOut,
+                "the SQL Server Browser did not respond",
+            )
+        })??;
+
+    parse_response(&buf[..len], instance)
+}
+
+// SVR_RESP = 0x05 RESP_SIZE RESP_DATA
+//
+// where RESP_DATA is a list of `key;value;` pairs for each instance, terminated by `;;`:
+//   ServerName;MYSERVER;InstanceName;SQLEXPRESS;IsClustered;No;Version;15.0.2000.5;tcp;49172;;
+fn parse_response(buf: &[u8], instance: &str) -> Result<u16, Error> {
+    if buf.len() < 3 || buf[0] != SVR_RESP {
+        return Err(err_protocol!(
+            "SQL Server Browser: unexpected response: {:?}",
+            buf
+        ));
+    }
+
+    let size = u16::from_le_bytes([buf[1], buf[2]]) as usize;
+    let data = buf.get(3..(3 + size)).ok_or_else(|| {
+        err_protocol!(
+            "SQL Server Browser: expected {} bytes of data but received {}",
+            size,
+            buf.len() - 3
+        )
+    })?;
+
+    let data = String::from_utf8_lossy(data);
+
+    for server in data.split(";;") {
+        let mut name = None;
+        let mut tcp = None;
+
+        let mut parts = server.split(';');
+
+        while let (Some(key), Some(value)) = (parts.next(), parts.next()) {
+            if key.eq_ignore_ascii_case("InstanceName") {
+                name = Some(value);
+            } else if key.eq_ignore_ascii_case("tcp") {
+                tcp = Some(value);
+            }
+        }
+
+        if !name.map_or(false, |name| name.eq_ignore_ascii_case(instance)) {
+            continue;
+        }
+
+        let tcp = tcp.ok_or_else(|| {
+            err_protocol!(
+                "SQL Server Browser: instance {:?} does not accept TCP connections",
+                instance
+            )
+        })?;
+
+        return tcp.parse().map_err(|_| {
+            err_protocol!(
+                "SQL Server Browser: invalid TCP port {:?} for instance {:?}",
+                tcp,
+                instance
+            )
+        });
+    }
+
+    Err(err_protocol!(
+        "SQL Server Browser: instance {:?} was not found",
+        instance
+    ))
+}
+
+#[test]
+fn test_parse_response() {
+    let data = "ServerName;MYSERVER;InstanceName;SQLEXPRESS;IsClustered;No;Version;15.0.2000.5;tcp;49172;np;\\\\MYSERVER\\pipe\\MSSQL$SQLEXPRESS\\sql\\query;;";
+
+    let mut buf = vec![SVR_RESP];
+    buf.extend(&(data.len() as u16).to_le_bytes());
+    buf.extend(data.as_bytes());
+
+    assert_eq!(parse_response(&buf, "sqlexpress").unwrap(), 49172);
+    assert!(parse_response(&buf, "OTHER").is_err());
+    assert!(parse_response(&buf[..10], "SQLEXPRESS").is_err());
+}
@@ -0,0 +1,506 @@
+use std::cmp;
+use std::fmt::Write;
+use std::ops::DerefMut;
+
+use crate::encode::Encode;
+use crate::error::Result;
+use crate::executor::Executor;
+use crate::mssql::io::MssqlBufMutExt;
+use crate::mssql::protocol::col_meta_data::Flags;
+use crate::mssql::protocol::done::Status as DoneStatus;
+use crate::mssql::protocol::message::Message;
+use crate::mssql::protocol::packet::{PacketHeader, PacketType, Status};
+use crate::mssql::protocol::type_info::{DataType, TypeInfo};
+use crate::mssql::{quote_identifier, Mssql, MssqlConnection, MssqlTypeInfo};
+use crate::pool::{Pool, PoolConnection};
+use crate::types::Type;
+
+const COL_META_DATA_TOKEN: u8 = 0x81;
+const ROW_TOKEN: u8 = 0xd1;
+const DONE_TOKEN: u8 = 0xfd;
+
+impl MssqlConnection {
+    /// Start a bulk load (`INSERT BULK`) into the named table. This is a more efficient way
+    /// to import data into SQL Server as compared to `INSERT`.
+    ///
+    /// The columns to load must be declared with [`MssqlBulkLoad::column`] before the first row
+    /// is added. Values are sent in the SQL type of the Rust type of each column and converted
+    /// by the server to the type of the column in the table.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(conn: &mut sqlx_core::mssql::MssqlConnection) -> sqlx_core::error::Result<()> {
+    /// let mut load = conn
+    ///     .bulk_load("dbo.users")
+    ///     .column::<i32>("id")
+    ///     .column::<String>("name")
+    ///     .tablock(true)
+    ///     .batch_size(10_000);
+    ///
+    /// for id in 0..100_000 {
+    ///     load.add_row().await?.push(id).push(format!("user {}", id));
+    /// }
+    ///
+    /// let rows_affected = load.finish().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ### Note
+    /// Rows are streamed to the server as they are added, but the rows of a batch are only
+    /// loaded once the batch is full or [`MssqlBulkLoad::finish`] is called. Dropping the
+    /// [`MssqlBulkLoad`] cancels the current batch.
+    pub fn bulk_load(&mut self, table: &str) -> MssqlBulkLoad<&mut Self> {
+        MssqlBulkLoad::new(self, table)
+    }
+}
+
+impl Pool<Mssql> {
+    /// Start a bulk load (`INSERT BULK`) into the named table.
+    ///
+    /// A single connection will be checked out for the duration.
+    ///
+    /// See [`MssqlConnection::bulk_load`] for details.
+    pub async fn bulk_load(&self, table: &str) -> Result<MssqlBulkLoad<PoolConnection<Mssql>>> {
+        Ok(MssqlBulkLoad::new(self.acquire().await?, table))
+    }
+}
+
+/// A bulk load into a table.
+///
+/// Created by [`MssqlConnection::bulk_load`] or [`Pool::bulk_load`].
+#[must_use = "rows are not sent to the server unless `.finish()` is called"]
+pub struct MssqlBulkLoad<C: DerefMut<Target = MssqlConnection>> {
+    conn: C,
+    table: String,
+    names: Vec<String>,
+    batch_size: Option<usize>,
+    tablock: bool,
+    check_constraints: bool,
+    fire_triggers: bool,
+    keep_nulls: bool,
+    rows: BulkRows,
+    rows_affected: u64,
+
+    // the `INSERT BULK` of the current batch was executed and its rows are being sent
+    started: bool,
+}
+
+/// A row of a bulk load, returned by [`MssqlBulkLoad::add_row`].
+pub struct MssqlBulkRow<'a> {
+    rows: &'a mut BulkRows,
+}
+
+struct BulkRows {
+    columns: Vec<TypeInfo>,
+
+    // data of the current batch that was not sent yet
+    buf: Vec<u8>,
+    // number of rows in the current batch
+    len: usize,
+
+    // number of values pushed onto the last row
+    row_len: usize,
+}
+
+impl<C: DerefMut<Target = MssqlConnection>> MssqlBulkLoad<C> {
+    fn new(conn: C, table: &str) -> Self {
+        Self {
+            conn,
+            table: table.to_owned(),
+            names: Vec::new(),
+            batch_size: None,
+            tablock: false,
+            check_constraints: false,
+            fire_triggers: false,
+            keep_nulls: false,
+            rows: BulkRows {
+                columns: Vec::new(),
+                buf: Vec::new(),
+                len: 0,
+                row_len: 0,
+            },
+            rows_affected: 0,
+            started: false,
+        }
+    }
+
+    /// Declare the next column to load, taking the SQL type of its values from `T`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a row has already been added.
+    pub fn column<T: Type<Mssql> + ?Sized>(mut self, name: &str) -> Self {
+        assert!(
+            self.rows.len == 0 && self.rows_affected == 0,
+            "columns must be declared before any row is added"
+        );
+
+        let mut ty = T::type_info().0;
+
+        if ty.collation.is_none() && matches!(ty.ty, DataType::BigVarChar | DataType::BigChar) {
+            // non-Unicode values are sent in the code page of the database
+            ty.collation = self.conn.stream.collation;
+        }
+
+        self.names.push(name.to_owned());
+        self.rows.columns.push(ty.into_column_type());
+
+        self
+    }
+
+    /// Sets the number of rows sent to the server in each batch.
+    ///
+    /// Each batch is loaded by a separate `INSERT BULK` statement, so when not in an explicit
+    /// transaction, the rows of a batch are committed once the batch has been sent.
+    ///
+    /// By default, all rows are sent in a single batch by [`finish`](Self::finish).
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = Some(cmp::max(batch_size, 1));
+        self
+    }
+
+    /// Sets whether a table-level lock is acquired for the duration of each batch.
+    ///
+    /// Defaults to `false`.
+    pub fn tablock(mut self, tablock: bool) -> Self {
+        self.tablock = tablock;
+        self
+    }
+
+    /// Sets whether the constraints on the table are checked during the load.
+    ///
+    /// Defaults to `false`, in which case the server marks the constraints as not trusted.
+    pub fn check_constraints(mut self, check_constraints: bool) -> Self {
+        self.check_constraints = check_constraints;
+        self
+    }
+
+    /// Sets whether insert triggers on the table are executed during the load.
+    ///
+    /// Defaults to `false`.
+    pub fn fire_triggers(mut self, fire_triggers: bool) -> Self {
+        self.fire_triggers = fire_triggers;
+        self
+    }
+
+    /// Sets whether `NULL` values are kept instead of replaced by the column defaults.
+    ///
+    /// Defaults to `false`.
+    pub fn keep_nulls(mut self, keep_nulls: bool) -> Self {
+        self.keep_nulls = keep_nulls;
+        self
+    }
+
+    /// Start a new row; push a value for each column onto the returned [`MssqlBulkRow`].
+    ///
+    /// If the current batch is full, it is finished first. Otherwise, the rows added so far
+    /// are sent to the server once they fill a packet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the previous row is missing values.
+    pub async fn add_row(&mut self) -> Result<MssqlBulkRow<'_>> {
+        self.rows.assert_row_complete();
+
+        if matches!(self.batch_size, Some(batch_size) if self.rows.len >= batch_size) {
+            self.finish_batch().await?;
+        } else if self.rows.buf.len() >= self.packet_len() {
+            self.send_packets().await?;
+        }
+
+        if self.rows.len == 0 {
+            self.put_col_meta_data();
+        }
+
+        self.rows.buf.push(ROW_TOKEN);
+        self.rows.len += 1;
+        self.rows.row_len = 0;
+
+        Ok(MssqlBulkRow {
+            rows: &mut self.rows,
+        })
+    }
+
+    /// Send any remaining rows to the server and return the total number of rows loaded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the last row is missing values.
+    pub async fn finish(mut self) -> Result<u64> {
+        self.rows.assert_row_complete();
+        self.finish_batch().await?;
+
+        Ok(self.rows_affected)
+    }
+
+    // the payload of a BulkLoadData packet
+    fn packet_len(&self) -> usize {
+        self.conn.stream.packet_size - 8
+    }
+
+    // the rows of a batch are preceded by their COLMETADATA
+    fn put_col_meta_data(&mut self) {
+        let buf = &mut self.rows.buf;
+
+        buf.push(COL_META_DATA_TOKEN);
+        buf.extend(&(self.rows.columns.len() as u16).to_le_bytes());
+
+        for (ty, name) in self.rows.columns.iter().zip(&self.names) {
+            buf.extend(&0_u32.to_le_bytes()); // [UserType]
+            buf.extend(&Flags::NULLABLE.bits().to_le_bytes()); // [Flags]
+            ty.put(buf); // [TYPE_INFO]
+            buf.put_b_varchar(name); // [ColName]
+        }
+    }
+
+    async fn begin_batch(&mut self) -> Result<()> {
+        let statement = self.statement();
+        self.conn.execute(&*statement).await?;
+
+        let conn = &mut *self.conn;
+
+        conn.stream.wait_until_ready().await?;
+        conn.stream.pending_done_count += 1;
+        conn.stream.request_in_flight = true;
+
+        self.started = true;
+
+        Ok(())
+    }
+
+    // sends the full packets of the rows added so far, without ending the batch
+    async fn send_packets(&mut self) -> Result<()> {
+        if !self.started {
+            self.begin_batch().await?;
+        }
+
+        // only whole packets are sent until the batch is finished
+        let len = self.rows.buf.len() - self.rows.buf.len() % self.packet_len();
+        let conn = &mut *self.conn;
+
+        let header = PacketHeader {
+            r#type: PacketType::BulkLoadData,
+            status: Status::NORMAL,
+            length: 0,
+            server_process_id: 0,
+            packet_id: conn.stream.new_packet_id(),
+        };
+
+        conn.stream
+            .write_packets(header, &self.rows.buf[..len], false)
+            .await?;
+
+        self.rows.buf.drain(..len);
+
+        Ok(())
+    }
+
+    async fn finish_batch(&mut self) -> Result<()> {
+        if self.rows.len == 0 {
+            return Ok(());
+        }
+
+        if !self.started {
+            self.begin_batch().await?;
+        }
+
+        // DONE
+        let buf = &mut self.rows.buf;
+        buf.push(DONE_TOKEN);
+        buf.extend(&0_u16.to_le_bytes()); // [Status], DONE_FINAL
+        buf.extend(&0_u16.to_le_bytes()); // [CurCmd]
+        buf.extend(&(self.rows.len as u64).to_le_bytes()); // [DoneRowCount]
+
+        let payload = std::mem::take(buf);
+
+        self.rows.len = 0;
+        self.rows.row_len = 0;
+        self.started = false;
+
+        let conn = &mut *self.conn;
+
+        let header = PacketHeader {
+            r#type: PacketType::BulkLoadData,
+            status: Status::END_OF_MESSAGE,
+            length: 0,
+            server_process_id: 0,
+            packet_id: conn.stream.new_packet_id(),
+        };
+
+        conn.stream.write_packet(header, &*payload).await?;
+
+        loop {
+            if let Message::Done(done) | Message::DoneProc(done) =
+                conn.stream.recv_message().await?
+            {
+                if done.status.contains(DoneStatus::DONE_COUNT) {
+                    self.rows_affected += done.affected_rows;
+                }
+
+                if !done.status.contains(DoneStatus::DONE_MORE) {
+                    conn.stream.handle_done(&done);
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // INSERT BULK table (column type, ...) WITH (option, ...)
+    fn statement(&self) -> String {
+        let mut statement = format!("INSERT BULK {} (", self.table);
+
+        for (i, (ty, name)) in self.rows.columns.iter().zip(&self.names).enumerate() {
+            if i > 0 {
+                statement.push_str(", ");
+            }
+
+            statement.push_str(&quote_identifier(name));
+            statement.push(' ');
+            column_type(ty, &mut statement);
+        }
+
+        statement.push(')');
+
+        let mut options = Vec::new();
+
+        if self.tablock {
+            options.push("TABLOCK".to_owned());
+        }
+
+        if self.check_constraints {
+            options.push("CHECK_CONSTRAINTS".to_owned());
+        }
+
+        if self.fire_triggers {
+            options.push("FIRE_TRIGGERS".to_owned());
+        }
+
+        if self.keep_nulls {
+            options.push("KEEP_NULLS".to_owned());
+        }
+
+        if let Some(batch_size) = self.batch_size {
+            options.push(format!("ROWS_PER_BATCH = {}", batch_size));
+        }
+
+        if !options.is_empty() {
+            let _ = write!(statement, " WITH ({})", options.join(", "));
+        }
+
+        statement
+    }
+}
+
+impl BulkRows {
+    fn assert_row_complete(&self) {
+        assert!(
+            self.len == 0 || self.row_len == self.columns.len(),
+            "row of bulk load has {} values but {} columns were declared",
+            self.row_len,
+            self.columns.len()
+        );
+    }
+}
+
+impl MssqlBulkRow<'_> {
+    /// Push the value of the next column onto this row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row already has a value for every column, or if the type of the value
+    /// is not compatible with the type of the column.
+    pub fn push<'q, T: 'q + Encode<'q, Mssql> + Type<Mssql>>(self, value: T) -> Self {
+        let rows = &mut *self.rows;
+
+        let column = rows.columns.get(rows.row_len).unwrap_or_else(|| {
+            panic!("bulk load only has {} columns declared", rows.columns.len())
+        });
+
+        let column = MssqlTypeInfo(column.clone());
+
+        assert!(
+            T::compatible(&column),
+            "value of type `{}` is not compatible with column {} of type `{}`",
+            std::any::type_name::<T>(),
+            rows.row_len,
+            column.0.name()
+        );
+
+        // values are always encoded using the TYPE_INFO of the column
+        column.0.put_value(&mut rows.buf, value);
+        rows.row_len += 1;
+
+        self
+    }
+}
+
+// the SQL type matching the TYPE_INFO sent in COLMETADATA
+fn column_type(ty: &TypeInfo, s: &mut String) {
+    match ty.ty {
+        DataType::NVarChar | DataType::NChar if ty.size <= 4000 => {
+            // `TypeInfo::put` always declares a maximum length of 8000 bytes
+            s.push_str(if ty.ty == DataType::NVarChar {
+                "nvarchar(4000)"
+            } else {
+                "nchar(4000)"
+            });
+        }
+
+        DataType::BigVarChar | DataType::BigChar if ty.size <= 8000 => {
+            s.push_str(if ty.ty == DataType::BigVarChar {
+                "varchar(8000)"
+            } else {
+                "char(8000)"
+            });
+        }
+
+        DataType::BigVarBinary | DataType::VarBinary | DataType::BigBinary | DataType::Binary => {
+            s.push_str("varbinary(8000)");
+        }
+
+        DataType::DateTimeN => {
+            s.push_str(if ty.size == 4 {
+                "smalldatetime"
+            } else {
+                "datetime"
+            });
+        }
+
+        DataType::DecimalN | DataType::NumericN => {
+            let _ = write!(s, "decimal({}, {})", ty.precision, ty.scale);
+        }
+
+        _ => ty.fmt(s),
+    }
+}
+
+#[test]
+fn test_column_type() {
+    let mut s = String::new();
+
+    column_type(
+        &<String as Type<Mssql>>::type_info().0.into_column_type(),
+        &mut s,
+    );
+    s.push(',');
+    column_type(
+        &<i64 as Type<Mssql>>::type_info().0.into_column_type(),
+        &mut s,
+    );
+    s.push(',');
+    column_type(
+        &<Vec<u8> as Type<Mssql>>::type_info().0.into_column_type(),
+        &mut s,
+    );
+    s.push(',');
+    column_type(
+        &<crate::mssql::types::MssqlVarChar as Type<Mssql>>::type_info()
+            .0
+            .into_column_type(),
+        &mut s,
+    );
+
+    assert_eq!(s, "nvarchar(4000),bigint,varbinary(8000),varchar(8000)");
+}
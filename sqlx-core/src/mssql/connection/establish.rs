@@ -2,22 +2,28 @@ use crate::common::StatementCache;
 use crate::error::Error;
 use crate::io::Decode;
 use crate::mssql::connection::stream::MssqlStream;
+use crate::mssql::connection::tls;
 use crate::mssql::protocol::login::Login7;
 use crate::mssql::protocol::message::Message;
 use crate::mssql::protocol::packet::PacketType;
-use crate::mssql::protocol::pre_login::{Encrypt, PreLogin, Version};
+use crate::mssql::protocol::pre_login::{PreLogin, Version};
 use crate::mssql::{MssqlConnectOptions, MssqlConnection};
 use crate::mssql::protocol::packet::PacketHeader;
 use crate::mssql::protocol::packet::Status;
 
 impl MssqlConnection {
     pub(crate) async fn establish(options: &MssqlConnectOptions) -> Result<Self, Error> {
+        // Acquire the access token for federated authentication, if any, before connecting
+        let access_token = match &options.access_token {
+            Some(access_token) => Some(access_token.get().await?),
+            None => None,
+        };
+
         let mut stream: MssqlStream = MssqlStream::connect(options).await?;
 
         // Send PRELOGIN to set up the context for login. The server should immediately
         // respond with a PRELOGIN message of its own.
 
-        // TODO: Encryption
         // TODO: Send the version of SQLx over
         let header = PacketHeader {
             r#type: PacketType::PreLogin,
@@ -31,7 +37,10 @@ impl MssqlConnection {
             header,
             PreLogin {
                 version: Version::default(),
-                encryption: Encrypt::NOT_SUPPORTED,
+                encryption: tls::client_encrypt(options),
+                fed_auth_required: access_token.as_ref().map(|_| true),
+                multiple_active_result_sets: Some(options.multiple_active_result_sets)
+                    .filter(|&mars| mars),
 
                 ..Default::default()
             },
@@ -40,7 +49,23 @@ impl MssqlConnection {
         stream.flush().await?;
 
         let (_, packet) = stream.recv_packet().await?;
-        let _ = PreLogin::decode(packet)?;
+        let pre_login = PreLogin::decode(packet)?;
+
+        // If the server agreed to encryption, perform the TLS handshake now; it is wrapped
+        // inside of PRELOGIN packets until it completes.
+        tls::maybe_upgrade(&mut stream, options, pre_login.encryption).await?;
+
+        // With MARS, every packet after PRELOGIN (and the TLS handshake) is sent within an SMP
+        // session, starting with LOGIN7.
+        if options.multiple_active_result_sets {
+            if pre_login.multiple_active_result_sets != Some(true) {
+                return Err(Error::Configuration(
+                    "server does not support Multiple Active Result Sets (MARS)".into(),
+                ));
+            }
+
+            stream.start_mars();
+        }
 
         // LOGIN7 defines the authentication rules for use between client and server
         let header = PacketHeader {
@@ -55,18 +80,28 @@ impl MssqlConnection {
             Login7 {
                 // FIXME: use a version constant
                 version: 0x74000004, // SQL Server 2012 - SQL Server 2019
-                client_program_version: 0,
-                client_pid: 0,
-                packet_size: 8192,
-                hostname: "",
-                username: &options.username,
-                password: options.password.as_deref().unwrap_or_default(),
-                app_name: "",
-                server_name: "",
-                client_interface_name: "",
-                language: "",
+                client_program_version: options.client_program_version,
+                client_pid: std::process::id(),
+                packet_size: options.packet_size,
+                hostname: options.hostname.as_deref().unwrap_or_default(),
+                username: if access_token.is_some() {
+                    ""
+                } else {
+                    &options.username
+                },
+                password: if access_token.is_some() {
+                    ""
+                } else {
+                    options.password.as_deref().unwrap_or_default()
+                },
+                app_name: options.application_name.as_deref().unwrap_or_default(),
+                server_name: &options.host,
+                client_interface_name: "sqlx",
+                language: options.language.as_deref().unwrap_or_default(),
                 database: &*options.database,
                 client_id: [0; 6],
+                access_token: access_token.as_deref(),
+                fed_auth_echo: pre_login.fed_auth_required.unwrap_or(false),
             },
         ).await?;
 
@@ -90,13 +125,9 @@ impl MssqlConnection {
             }
         }
 
-        // FIXME: Do we need to expose the capacity count here? It's not tied to
-        //        server-side resources but just .prepare() calls which return
-        //        client-side data.
-
         Ok(Self {
             stream,
-            cache_statement: StatementCache::new(1024),
+            cache_statement: StatementCache::new(options.statement_cache_capacity),
             log_settings: options.log_settings.clone(),
         })
     }
@@ -2,8 +2,7 @@ use crate::describe::Describe;
 use crate::error::Error;
 use crate::executor::{Execute, Executor};
 use crate::logger::QueryLogger;
-use crate::mssql::connection::prepare::prepare;
-use crate::mssql::protocol::col_meta_data::Flags;
+use crate::mssql::connection::prepare::{describe, prepare};
 use crate::mssql::protocol::done::Status as DoneStatus;
 use crate::mssql::protocol::message::Message;
 use crate::mssql::protocol::packet::PacketType;
@@ -26,6 +25,7 @@ impl MssqlConnection {
     async fn run(&mut self, query: &str, arguments: Option<MssqlArguments>) -> Result<(), Error> {
         self.stream.wait_until_ready().await?;
         self.stream.pending_done_count += 1;
+        self.stream.request_in_flight = true;
 
         if let Some(mut arguments) = arguments {
             let proc = Either::Right(Procedure::ExecuteSql);
@@ -42,6 +42,11 @@ impl MssqlConnection {
                 // Add the list of SQL parameters _after_ our RPC parameters
                 proc_args.append(&mut arguments);
             }
+
+            if let Some(collation) = self.stream.collation {
+                proc_args.apply_collation(collation);
+            }
+
             let header = PacketHeader {
                 r#type: PacketType::Rpc,
                 status: Status::END_OF_MESSAGE,
@@ -224,26 +229,6 @@ impl<'c> Executor<'c> for &'c mut MssqlConnection {
     where
         'c: 'e,
     {
-        Box::pin(async move {
-            let metadata = prepare(self, sql).await?;
-
-            let mut nullable = Vec::with_capacity(metadata.columns.len());
-
-            for col in metadata.columns.iter() {
-                // if col.flags.contains(Flags::NULLABLE) {
-                //     println!("col: {} nullable.\n", col.name);
-                // } else {
-                //     println!("col: {} not nullable################\n", col.name);
-                // }
-                nullable.push(Some(col.flags.contains(Flags::NULLABLE)));
-            }
-            // println!("nullable*********{:?}\n", nullable);
-
-            Ok(Describe {
-                nullable,
-                columns: (metadata.columns).clone(),
-                parameters: None,
-            })
-        })
+        Box::pin(describe(self, sql))
     }
 }
@@ -1,17 +1,22 @@
 use crate::decode::Decode;
+use crate::describe::Describe;
 use crate::error::Error;
+use crate::mssql::protocol::col_meta_data::Flags;
 use crate::mssql::protocol::done::Status as DoneStatus;
 use crate::mssql::protocol::message::Message;
+use crate::mssql::protocol::packet::PacketHeader;
 use crate::mssql::protocol::packet::PacketType;
+use crate::mssql::protocol::packet::Status;
 use crate::mssql::protocol::rpc::{OptionFlags, Procedure, RpcRequest};
+use crate::mssql::protocol::type_info::TypeInfo;
 use crate::mssql::statement::MssqlStatementMetadata;
 use crate::mssql::{Mssql, MssqlArguments, MssqlConnection, MssqlTypeInfo, MssqlValueRef};
+use crate::query::query;
+use crate::row::Row;
 use either::Either;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::sync::Arc;
-use crate::mssql::protocol::packet::PacketHeader;
-use crate::mssql::protocol::packet::Status;
 
 pub(crate) async fn prepare(
     conn: &mut MssqlConnection,
@@ -21,38 +26,25 @@ pub(crate) async fn prepare(
         return Ok(metadata.clone());
     }
 
-    // NOTE: this does not support unicode identifiers; as we don't even support
-    //       named parameters (yet) this is probably fine, for now
-
-    static PARAMS_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"@p[[:alnum:]]+").unwrap());
-
-    let mut params = String::new();
-
-    for m in PARAMS_RE.captures_iter(sql) {
-        if !params.is_empty() {
-            params.push_str(",");
-        }
-
-        params.push_str(&m[0]);
-
-        // NOTE: this means that a query! of `SELECT @p1` will have the macros believe
-        //       it will return nvarchar(1); this is a greater issue with `query!` that we
-        //       we need to circle back to. This doesn't happen much in practice however.
-        params.push_str(" nvarchar(1)");
-    }
-
-    let params = if params.is_empty() {
-        None
-    } else {
-        Some(&*params)
-    };
+    let (declarations, parameters) = describe_parameters(conn, sql).await?;
 
     let mut args = MssqlArguments::default();
 
     args.declare("", 0_i32);
-    args.add_unnamed(params);
+    args.add_unnamed(declarations.as_deref());
     args.add_unnamed(sql);
     args.add_unnamed(0x0001_i32); // 1 = SEND_METADATA
+
+    // make sure the results of any previous request are consumed (or cancelled) first
+    conn.stream.wait_until_ready().await?;
+
+    // the column metadata is only sent if the statement returns rows
+    conn.stream.columns = Default::default();
+    conn.stream.column_names = Default::default();
+
+    conn.stream.pending_done_count += 1;
+    conn.stream.request_in_flight = true;
+
     let header = PacketHeader {
         r#type: PacketType::Rpc,
         status: Status::END_OF_MESSAGE,
@@ -60,23 +52,18 @@ pub(crate) async fn prepare(
         server_process_id: 0,
         packet_id: 1,
     };
-    conn.stream.write_packet(
-        header,
-        RpcRequest {
-            transaction_descriptor: conn.stream.transaction_descriptor,
-            arguments: &args,
-            // [sp_prepare] will emit the column meta data
-            // small issue is that we need to declare all the used placeholders with a "fallback" type
-            // we currently use regex to collect them; false positives are *okay* but false
-            // negatives would break the query
-            procedure: Either::Right(Procedure::Prepare),
-            options: OptionFlags::empty(),
-        },
-    ).await?;
-
-    conn.stream.flush().await?;
-    conn.stream.wait_until_ready().await?;
-    conn.stream.pending_done_count += 1;
+    conn.stream
+        .write_packet(
+            header,
+            RpcRequest {
+                transaction_descriptor: conn.stream.transaction_descriptor,
+                arguments: &args,
+                // [sp_prepare] will emit the column meta data
+                procedure: Either::Right(Procedure::Prepare),
+                options: OptionFlags::empty(),
+            },
+        )
+        .await?;
 
     let mut id: Option<i32> = None;
 
@@ -107,6 +94,11 @@ pub(crate) async fn prepare(
     if let Some(id) = id {
         let mut args = MssqlArguments::default();
         args.add_unnamed(id);
+
+        conn.stream.wait_until_ready().await?;
+        conn.stream.pending_done_count += 1;
+        conn.stream.request_in_flight = true;
+
         let header = PacketHeader {
             r#type: PacketType::Rpc,
             status: Status::END_OF_MESSAGE,
@@ -114,19 +106,17 @@ pub(crate) async fn prepare(
             server_process_id: 0,
             packet_id: 1,
         };
-        conn.stream.write_packet(
-            header,
-            RpcRequest {
-                transaction_descriptor: conn.stream.transaction_descriptor,
-                arguments: &args,
-                procedure: Either::Right(Procedure::Unprepare),
-                options: OptionFlags::empty(),
-            },
-        ).await?;
-
-        conn.stream.flush().await?;
-        conn.stream.wait_until_ready().await?;
-        conn.stream.pending_done_count += 1;
+        conn.stream
+            .write_packet(
+                header,
+                RpcRequest {
+                    transaction_descriptor: conn.stream.transaction_descriptor,
+                    arguments: &args,
+                    procedure: Either::Right(Procedure::Unprepare),
+                    options: OptionFlags::empty(),
+                },
+            )
+            .await?;
 
         loop {
             let message = conn.stream.recv_message().await?;
@@ -148,9 +138,185 @@ pub(crate) async fn prepare(
     let metadata = Arc::new(MssqlStatementMetadata {
         columns: conn.stream.columns.as_ref().clone(),
         column_names: conn.stream.column_names.as_ref().clone(),
+        declarations,
+        parameters,
     });
 
     conn.cache_statement.insert(sql, metadata.clone());
 
     Ok(metadata)
 }
+
+pub(crate) async fn describe(
+    conn: &mut MssqlConnection,
+    sql: &str,
+) -> Result<Describe<Mssql>, Error> {
+    let metadata = prepare(conn, sql).await?;
+    let nullable = describe_nullable(conn, sql, &metadata).await?;
+
+    Ok(Describe {
+        nullable,
+        columns: metadata.columns.clone(),
+        parameters: metadata.parameters.clone().map(Either::Left),
+    })
+}
+
+// ask the server for the types of the parameters of the statement; returns their declarations,
+// e.g. `@p1 int,@p2 nvarchar(50)`, and their types if all are positional
+async fn describe_parameters(
+    conn: &mut MssqlConnection,
+    sql: &str,
+) -> Result<(Option<String>, Option<Vec<MssqlTypeInfo>>), Error> {
+    let rows = match query("EXEC sp_describe_undeclared_parameters @tsql = @p1")
+        .bind(sql)
+        .fetch_all(&mut *conn)
+        .await
+    {
+        Ok(rows) => rows,
+
+        // the server could not deduce the type of every parameter, e.g. in `SELECT @p1`
+        Err(Error::Database(_)) => return Ok((fallback_declarations(sql), None)),
+
+        Err(error) => return Err(error),
+    };
+
+    let mut parameters = Vec::with_capacity(rows.len());
+
+    for row in rows {
+        let name: String = row.try_get("name")?;
+        let declaration: String = row.try_get("suggested_system_type_name")?;
+
+        let type_info = TypeInfo::from_system_type(
+            row.try_get("suggested_system_type_id")?,
+            row.try_get("suggested_max_length")?,
+            row.try_get("suggested_precision")?,
+            row.try_get("suggested_scale")?,
+        );
+
+        parameters.push((positional_ordinal(&name), name, declaration, type_info));
+    }
+
+    if parameters.is_empty() {
+        return Ok((None, Some(Vec::new())));
+    }
+
+    // positional parameters are bound in the order of their number: @p1, @p2, ...
+    parameters.sort_by_key(|(ordinal, ..)| *ordinal);
+
+    let mut declarations = String::new();
+
+    for (_, name, declaration, _) in &parameters {
+        if !declarations.is_empty() {
+            declarations.push(',');
+        }
+
+        declarations.push_str(name);
+        declarations.push(' ');
+        declarations.push_str(declaration);
+    }
+
+    let types = parameters
+        .into_iter()
+        .enumerate()
+        .map(
+            |(i, (ordinal, _, _, type_info))| match (ordinal, type_info) {
+                (Some(ordinal), Some(type_info)) if ordinal == i + 1 => {
+                    Some(MssqlTypeInfo(type_info))
+                }
+                _ => None,
+            },
+        )
+        .collect();
+
+    Ok((Some(declarations), types))
+}
+
+// the number of a positional parameter, e.g. `@p1`
+fn positional_ordinal(name: &str) -> Option<usize> {
+    let number = name
+        .strip_prefix("@p")
+        .or_else(|| name.strip_prefix("@P"))?;
+
+    number.parse().ok()
+}
+
+fn fallback_declarations(sql: &str) -> Option<String> {
+    // NOTE: this does not support unicode identifiers; as we don't even support
+    //       named parameters (yet) this is probably fine, for now
+
+    static PARAMS_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"@p[[:alnum:]]+").unwrap());
+
+    let mut params = String::new();
+
+    for m in PARAMS_RE.captures_iter(sql) {
+        if !params.is_empty() {
+            params.push_str(",");
+        }
+
+        params.push_str(&m[0]);
+
+        // NOTE: this means that a query! of `SELECT @p1` will have the macros believe
+        //       it will return nvarchar(1); this is a greater issue with `query!` that we
+        //       we need to circle back to. This doesn't happen much in practice however.
+        params.push_str(" nvarchar(1)");
+    }
+
+    if params.is_empty() {
+        None
+    } else {
+        Some(params)
+    }
+}
+
+// ask the server which columns of the result can be NULL; the NULLABLE flag of the column
+// metadata is used for any column the server cannot describe
+async fn describe_nullable(
+    conn: &mut MssqlConnection,
+    sql: &str,
+    metadata: &MssqlStatementMetadata,
+) -> Result<Vec<Option<bool>>, Error> {
+    let mut nullable: Vec<Option<bool>> = metadata
+        .columns
+        .iter()
+        .map(|column| Some(column.flags.contains(Flags::NULLABLE)))
+        .collect();
+
+    let rows = match query("EXEC sp_describe_first_result_set @tsql = @p1, @params = @p2")
+        .bind(sql)
+        .bind(metadata.declarations.as_deref())
+        .fetch_all(&mut *conn)
+        .await
+    {
+        Ok(rows) => rows,
+
+        // e.g. the statement uses a temporary table
+        Err(Error::Database(_)) => return Ok(nullable),
+
+        Err(error) => return Err(error),
+    };
+
+    for row in rows {
+        if row.try_get::<bool, _>("is_hidden")? {
+            continue;
+        }
+
+        let ordinal: i32 = row.try_get("column_ordinal")?;
+
+        if let Some(column) = usize::try_from(ordinal - 1)
+            .ok()
+            .and_then(|index| nullable.get_mut(index))
+        {
+            *column = Some(row.try_get("is_nullable")?);
+        }
+    }
+
+    Ok(nullable)
+}
+
+#[test]
+fn test_positional_ordinal() {
+    assert_eq!(positional_ordinal("@p1"), Some(1));
+    assert_eq!(positional_ordinal("@P12"), Some(12));
+    assert_eq!(positional_ordinal("@name"), None);
+    assert_eq!(positional_ordinal("@p"), None);
+}
@@ -0,0 +1,291 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use either::Either;
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::error::{mismatched_types, Error};
+use crate::logger::QueryLogger;
+use crate::mssql::protocol::done::Status as DoneStatus;
+use crate::mssql::protocol::message::Message;
+use crate::mssql::protocol::packet::{PacketHeader, PacketType, Status};
+use crate::mssql::protocol::rpc::{OptionFlags, RpcRequest};
+use crate::mssql::{
+    Mssql, MssqlArguments, MssqlConnection, MssqlRow, MssqlTypeInfo, MssqlValue, MssqlValueRef,
+};
+use crate::type_info::TypeInfo;
+use crate::types::Type;
+use crate::value::{Value, ValueRef};
+
+/// A call to a stored procedure, built with [`MssqlConnection::call_procedure`].
+///
+/// Parameters are sent in the order they are bound. Positional parameters must be bound
+/// before any named parameter, as required by SQL Server.
+#[must_use = "a procedure call does nothing unless `.execute()` is called"]
+pub struct MssqlProcedureCall<'c> {
+    conn: &'c mut MssqlConnection,
+    procedure: String,
+    arguments: MssqlArguments,
+}
+
+/// The result of a stored procedure call; see [`MssqlConnection::call_procedure`].
+#[derive(Default)]
+pub struct MssqlProcedureResult {
+    return_status: Option<i32>,
+    outputs: Vec<(String, MssqlValue)>,
+    rows: Vec<MssqlRow>,
+    rows_affected: u64,
+}
+
+impl MssqlConnection {
+    /// Call the named stored procedure using an RPC request.
+    ///
+    /// Unlike wrapping the call in an `EXEC` batch, this makes the values of `OUTPUT`
+    /// parameters and the return status of the procedure available on the
+    /// [`MssqlProcedureResult`].
+    ///
+    /// ```rust,no_run
+    /// # async fn example(conn: &mut sqlx_core::mssql::MssqlConnection) -> sqlx_core::error::Result<()> {
+    /// let result = conn
+    ///     .call_procedure("dbo.transfer_funds")
+    ///     .bind(1_i32)
+    ///     .bind_named("@amount", 100_i64)
+    ///     .bind_output::<i64>("@balance")
+    ///     .execute()
+    ///     .await?;
+    ///
+    /// let balance: Option<i64> = result.try_get("@balance")?;
+    /// let status = result.return_status();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn call_procedure(&mut self, procedure: &str) -> MssqlProcedureCall<'_> {
+        MssqlProcedureCall {
+            conn: self,
+            procedure: procedure.to_owned(),
+            arguments: MssqlArguments::default(),
+        }
+    }
+}
+
+impl<'c> MssqlProcedureCall<'c> {
+    /// Bind a positional input parameter.
+    pub fn bind<'q, T: 'q + Encode<'q, Mssql> + Type<Mssql>>(mut self, value: T) -> Self {
+        self.arguments.add_unnamed(value);
+        self
+    }
+
+    /// Bind an input parameter by name, e.g. `@amount`.
+    pub fn bind_named<'q, T: 'q + Encode<'q, Mssql> + Type<Mssql>>(
+        mut self,
+        name: &str,
+        value: T,
+    ) -> Self {
+        self.arguments.add_named(&param_name(name), value);
+        self
+    }
+
+    /// Bind an `OUTPUT` parameter by name that is initially `NULL`.
+    ///
+    /// The SQL type of the parameter is taken from `T`. Strings and binary values are declared
+    /// as `nvarchar(4000)`, `varchar(8000)` or `varbinary(8000)`, so longer values returned by
+    /// the procedure are truncated.
+    pub fn bind_output<'q, T: 'q + Encode<'q, Mssql> + Type<Mssql>>(mut self, name: &str) -> Self {
+        self.arguments.declare_output::<T>(&param_name(name));
+        self
+    }
+
+    /// Bind an `OUTPUT` parameter by name with an initial value.
+    ///
+    /// Strings and binary values are declared with the same sizes as in
+    /// [`bind_output`](Self::bind_output), unless the initial value is longer.
+    pub fn bind_inout<'q, T: 'q + Encode<'q, Mssql> + Type<Mssql>>(
+        mut self,
+        name: &str,
+        value: T,
+    ) -> Self {
+        self.arguments.declare(&param_name(name), value);
+        self
+    }
+
+    /// Execute the procedure and collect its result sets, output parameters and return status.
+    pub async fn execute(self) -> Result<MssqlProcedureResult, Error> {
+        let MssqlProcedureCall {
+            conn,
+            procedure,
+            mut arguments,
+        } = self;
+
+        let mut logger = QueryLogger::new(&procedure, conn.log_settings.clone());
+
+        conn.stream.wait_until_ready().await?;
+        conn.stream.pending_done_count += 1;
+        conn.stream.request_in_flight = true;
+
+        if let Some(collation) = conn.stream.collation {
+            arguments.apply_collation(collation);
+        }
+
+        let header = PacketHeader {
+            r#type: PacketType::Rpc,
+            status: Status::END_OF_MESSAGE,
+            length: 0,
+            server_process_id: 0,
+            packet_id: conn.stream.new_packet_id(),
+        };
+
+        conn.stream
+            .write_packet(
+                header,
+                RpcRequest {
+                    transaction_descriptor: conn.stream.transaction_descriptor,
+                    arguments: &arguments,
+                    procedure: Either::Left(&procedure),
+                    options: OptionFlags::empty(),
+                },
+            )
+            .await?;
+
+        let mut result = MssqlProcedureResult::default();
+
+        loop {
+            match conn.stream.recv_message().await? {
+                Message::Row(row) => {
+                    logger.increment_rows_returned();
+
+                    result.rows.push(MssqlRow {
+                        row,
+                        columns: Arc::clone(&conn.stream.columns),
+                        column_names: Arc::clone(&conn.stream.column_names),
+                    });
+                }
+
+                Message::ReturnStatus(status) => {
+                    result.return_status = Some(status.value);
+                }
+
+                Message::ReturnValue(value) => {
+                    result.outputs.push((
+                        value.param_name,
+                        MssqlValue {
+                            type_info: MssqlTypeInfo(value.type_info),
+                            data: value.value,
+                        },
+                    ));
+                }
+
+                Message::Done(done) | Message::DoneProc(done) => {
+                    if done.status.contains(DoneStatus::DONE_COUNT) {
+                        logger.increase_rows_affected(done.affected_rows);
+                        result.rows_affected += done.affected_rows;
+                    }
+
+                    if !done.status.contains(DoneStatus::DONE_MORE) {
+                        conn.stream.handle_done(&done);
+                        break;
+                    }
+                }
+
+                Message::DoneInProc(done) => {
+                    if done.status.contains(DoneStatus::DONE_COUNT) {
+                        logger.increase_rows_affected(done.affected_rows);
+                        result.rows_affected += done.affected_rows;
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl MssqlProcedureResult {
+    /// The value of the `RETURN` statement of the procedure, if the server sent one.
+    pub fn return_status(&self) -> Option<i32> {
+        self.return_status
+    }
+
+    /// The number of rows affected by the statements in the procedure.
+    pub fn rows_affected(&self) -> u64 {
+        self.rows_affected
+    }
+
+    /// The rows of all result sets returned by the procedure.
+    pub fn rows(&self) -> &[MssqlRow] {
+        &self.rows
+    }
+
+    /// Consume the result, returning the rows of all result sets.
+    pub fn into_rows(self) -> Vec<MssqlRow> {
+        self.rows
+    }
+
+    /// Decode the value of the named `OUTPUT` parameter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameter was not returned or if the value could not be decoded.
+    /// See [`try_get`](Self::try_get) for a non-panicking version.
+    #[track_caller]
+    pub fn get<'r, T>(&'r self, name: &str) -> T
+    where
+        T: Decode<'r, Mssql> + Type<Mssql>,
+    {
+        self.try_get(name).unwrap()
+    }
+
+    /// Decode the value of the named `OUTPUT` parameter.
+    ///
+    /// The leading `@` of the name is optional and the name is matched case-insensitively.
+    pub fn try_get<'r, T>(&'r self, name: &str) -> Result<T, Error>
+    where
+        T: Decode<'r, Mssql> + Type<Mssql>,
+    {
+        let value = self.try_get_raw(name)?;
+
+        if !value.is_null() {
+            let ty = value.type_info();
+
+            if !ty.is_null() && !T::compatible(&ty) {
+                return Err(Error::ColumnDecode {
+                    index: format!("{:?}", name),
+                    source: mismatched_types::<Mssql, T>(&ty),
+                });
+            }
+        }
+
+        T::decode(value).map_err(|source| Error::ColumnDecode {
+            index: format!("{:?}", name),
+            source,
+        })
+    }
+
+    /// Get the raw value of the named `OUTPUT` parameter.
+    pub fn try_get_raw(&self, name: &str) -> Result<MssqlValueRef<'_>, Error> {
+        let name = name.trim_start_matches('@');
+
+        self.outputs
+            .iter()
+            .find(|(param, _)| param.trim_start_matches('@').eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_ref())
+            .ok_or_else(|| Error::ColumnNotFound(name.into()))
+    }
+}
+
+// parameter names in an RPC request must start with `@`
+fn param_name(name: &str) -> Cow<'_, str> {
+    if name.starts_with('@') {
+        Cow::Borrowed(name)
+    } else {
+        Cow::Owned(format!("@{}", name))
+    }
+}
+
+#[test]
+fn test_param_name() {
+    assert_eq!(param_name("@amount"), "@amount");
+    assert_eq!(param_name("amount"), "@amount");
+}
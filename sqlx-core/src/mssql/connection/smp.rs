@@ -0,0 +1,639 @@
+use std::cmp;
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+
+use bitflags::bitflags;
+use bytes::{Buf, Bytes};
+use futures_core::ready;
+use sqlx_rt::{AsyncRead, AsyncWrite};
+
+use crate::mssql::connection::tls_prelogin_stream_wrapper::poll_read_slice;
+use crate::net::{PollReadBuf, PollReadOut};
+
+// Multiple Active Result Sets (MARS) multiplexes several sessions, each carrying its own
+// stream of TDS packets, over a single connection with the Session Multiplex Protocol (SMP).
+//
+// Every TDS packet of a session is wrapped in an SMP DATA packet. Either side may only send
+// DATA packets up to the window advertised by the other side, which grows as packets are
+// consumed and is announced with ACK packets.
+//
+// SMP sits *above* the TLS layer and below `BufStream`, so the TDS layer is unaware of it.
+//
+// https://docs.microsoft.com/en-us/openspecs/windows_protocols/mc-smp
+
+const SMID: u8 = 0x53;
+
+const HEADER_BYTES: usize = 16;
+
+// the number of DATA packets that may be received but not yet read
+const WINDOW: u32 = 4;
+
+bitflags! {
+    struct Flags: u8 {
+        const SYN = 0x01;
+        const ACK = 0x02;
+        const FIN = 0x04;
+        const DATA = 0x08;
+    }
+}
+
+#[derive(Debug)]
+struct Header {
+    flags: Flags,
+    sid: u16,
+    length: u32,
+    seq_num: u32,
+    window: u32,
+}
+
+impl Header {
+    fn get(buf: &[u8; HEADER_BYTES]) -> io::Result<Self> {
+        let mut buf = &buf[..];
+
+        if buf.get_u8() != SMID {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "received a packet that is not an SMP packet",
+            ));
+        }
+
+        let header = Self {
+            flags: Flags::from_bits_truncate(buf.get_u8()),
+            sid: buf.get_u16_le(),
+            length: buf.get_u32_le(),
+            seq_num: buf.get_u32_le(),
+            window: buf.get_u32_le(),
+        };
+
+        if (header.length as usize) < HEADER_BYTES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid SMP packet length {}", header.length),
+            ));
+        }
+
+        Ok(header)
+    }
+
+    fn put(&self, buf: &mut Vec<u8>) {
+        buf.push(SMID);
+        buf.push(self.flags.bits());
+        buf.extend(&self.sid.to_le_bytes());
+        buf.extend(&self.length.to_le_bytes());
+        buf.extend(&self.seq_num.to_le_bytes());
+        buf.extend(&self.window.to_le_bytes());
+    }
+}
+
+// A stream that is either the connection itself or a MARS session on it.
+pub(crate) enum SmpStream<S> {
+    // MARS is disabled, or has not been started yet
+    Direct(S),
+
+    Session(SmpSession<S>),
+
+    Starting,
+}
+
+impl<S> SmpStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    // every packet sent after this is sent within the first MARS session
+    pub(crate) fn start_mars(&mut self) {
+        *self = match std::mem::replace(self, SmpStream::Starting) {
+            SmpStream::Direct(socket) => {
+                let mux = Arc::new(Mutex::new(Mux {
+                    socket,
+                    sessions: HashMap::new(),
+                    next_sid: 0,
+                    header_buf: [0; HEADER_BYTES],
+                    header_pos: 0,
+                    header: None,
+                    payload: Vec::new(),
+                    payload_pos: 0,
+                    wbuf: Vec::new(),
+                    wpos: 0,
+                }));
+
+                SmpStream::Session(SmpSession::open(mux))
+            }
+
+            stream => stream,
+        };
+    }
+
+    // the socket is only reachable directly until MARS is started, which is after the TLS
+    // handshake; a session shares it with all of the other sessions
+    pub(crate) fn socket_mut(&mut self) -> io::Result<&mut S> {
+        match self {
+            SmpStream::Direct(socket) => Ok(socket),
+
+            SmpStream::Session(_) | SmpStream::Starting => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "the socket of a MARS connection is shared by its sessions",
+            )),
+        }
+    }
+
+    pub(crate) fn open_session(&self) -> Option<SmpSession<S>> {
+        match self {
+            SmpStream::Session(session) => Some(SmpSession::open(session.mux.clone())),
+            _ => None,
+        }
+    }
+}
+
+impl<S> AsyncRead for SmpStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut PollReadBuf<'_>,
+    ) -> Poll<io::Result<PollReadOut>> {
+        match &mut *self {
+            SmpStream::Direct(s) => Pin::new(s).poll_read(cx, buf),
+            SmpStream::Session(s) => Pin::new(s).poll_read(cx, buf),
+
+            SmpStream::Starting => Poll::Ready(Err(io::ErrorKind::ConnectionAborted.into())),
+        }
+    }
+}
+
+impl<S> AsyncWrite for SmpStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match &mut *self {
+            SmpStream::Direct(s) => Pin::new(s).poll_write(cx, buf),
+            SmpStream::Session(s) => Pin::new(s).poll_write(cx, buf),
+
+            SmpStream::Starting => Poll::Ready(Err(io::ErrorKind::ConnectionAborted.into())),
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut *self {
+            SmpStream::Direct(s) => Pin::new(s).poll_flush(cx),
+            SmpStream::Session(s) => Pin::new(s).poll_flush(cx),
+
+            SmpStream::Starting => Poll::Ready(Err(io::ErrorKind::ConnectionAborted.into())),
+        }
+    }
+
+    #[cfg(feature = "_rt-tokio")]
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut *self {
+            SmpStream::Direct(s) => Pin::new(s).poll_shutdown(cx),
+            SmpStream::Session(s) => s.poll_close_session(cx),
+
+            SmpStream::Starting => Poll::Ready(Err(io::ErrorKind::ConnectionAborted.into())),
+        }
+    }
+
+    #[cfg(feature = "_rt-async-std")]
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut *self {
+            SmpStream::Direct(s) => Pin::new(s).poll_close(cx),
+            SmpStream::Session(s) => s.poll_close_session(cx),
+
+            SmpStream::Starting => Poll::Ready(Err(io::ErrorKind::ConnectionAborted.into())),
+        }
+    }
+}
+
+// the connection shared by all of the sessions
+struct Mux<S> {
+    socket: S,
+    sessions: HashMap<u16, Session>,
+    next_sid: u16,
+
+    // the SMP packet being read from the socket
+    header_buf: [u8; HEADER_BYTES],
+    header_pos: usize,
+    header: Option<Header>,
+    payload: Vec<u8>,
+    payload_pos: usize,
+
+    // SMP packets waiting to be written to the socket
+    wbuf: Vec<u8>,
+    wpos: usize,
+}
+
+struct Session {
+    // payloads of the DATA packets received that have not been read yet
+    received: VecDeque<Bytes>,
+
+    // the highest sequence number we accept from the server
+    window: u32,
+
+    // the sequence number of the last DATA packet we sent, and the highest the server accepts
+    seq_num: u32,
+    server_window: u32,
+
+    // set once the server has closed the session
+    closed: bool,
+
+    // the task waiting for data or for the window of the server to grow
+    waker: Option<Waker>,
+}
+
+impl<S> Mux<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn queue_packet(&mut self, sid: u16, flags: Flags, payload: &[u8]) {
+        let session = match self.sessions.get_mut(&sid) {
+            Some(session) => session,
+            None => return,
+        };
+
+        if flags.contains(Flags::DATA) {
+            session.seq_num = session.seq_num.wrapping_add(1);
+        }
+
+        Header {
+            flags,
+            sid,
+            length: (HEADER_BYTES + payload.len()) as u32,
+            seq_num: session.seq_num,
+            window: session.window,
+        }
+        .put(&mut self.wbuf);
+
+        self.wbuf.extend_from_slice(payload);
+    }
+
+    fn poll_flush_socket(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.wpos < self.wbuf.len() {
+            let n = ready!(Pin::new(&mut self.socket).poll_write(cx, &self.wbuf[self.wpos..]))?;
+
+            if n == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+
+            self.wpos += n;
+        }
+
+        self.wbuf.clear();
+        self.wpos = 0;
+
+        Pin::new(&mut self.socket).poll_flush(cx)
+    }
+
+    // reads the next SMP packet from the socket and hands it to its session
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if self.header.is_none() {
+            while self.header_pos < HEADER_BYTES {
+                let n = ready!(poll_read_slice(
+                    &mut self.socket,
+                    cx,
+                    &mut self.header_buf[self.header_pos..]
+                ))?;
+
+                if n == 0 {
+                    return Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()));
+                }
+
+                self.header_pos += n;
+            }
+
+            let header = Header::get(&self.header_buf)?;
+
+            self.header_pos = 0;
+            self.payload = vec![0; header.length as usize - HEADER_BYTES];
+            self.payload_pos = 0;
+            self.header = Some(header);
+        }
+
+        while self.payload_pos < self.payload.len() {
+            let n = ready!(poll_read_slice(
+                &mut self.socket,
+                cx,
+                &mut self.payload[self.payload_pos..]
+            ))?;
+
+            if n == 0 {
+                return Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()));
+            }
+
+            self.payload_pos += n;
+        }
+
+        let header = match self.header.take() {
+            Some(header) => header,
+            None => return Poll::Ready(Ok(())),
+        };
+
+        let payload = std::mem::take(&mut self.payload);
+
+        // packets for a session that we closed are discarded
+        if let Some(session) = self.sessions.get_mut(&header.sid) {
+            // every packet carries the current window of the server
+            session.server_window = header.window;
+
+            if header.flags.contains(Flags::DATA) {
+                session.received.push_back(payload.into());
+            }
+
+            if header.flags.contains(Flags::FIN) {
+                session.closed = true;
+            }
+        }
+
+        // only the task that polled the socket last is woken when it is ready again; wake
+        // every waiting task so each one can check for its packets and poll the socket again
+        self.wake_all();
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<S> Mux<S> {
+    fn wake_all(&mut self) {
+        for session in self.sessions.values_mut() {
+            if let Some(waker) = session.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+// A MARS session. The TDS packets written to it are sent once it is flushed.
+pub(crate) struct SmpSession<S> {
+    mux: Arc<Mutex<Mux<S>>>,
+    sid: u16,
+
+    // what is left of the payload of the DATA packet being read
+    rbuf: Bytes,
+
+    // TDS packets that have been written but not sent
+    wbuf: Vec<u8>,
+    wpos: usize,
+
+    fin_sent: bool,
+}
+
+impl<S> SmpSession<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn open(mux: Arc<Mutex<Mux<S>>>) -> Self {
+        let sid = {
+            let mut guard = lock(&mux);
+
+            let sid = guard.next_sid;
+            guard.next_sid = guard.next_sid.wrapping_add(1);
+
+            guard.sessions.insert(
+                sid,
+                Session {
+                    received: VecDeque::new(),
+                    window: WINDOW,
+                    seq_num: 0,
+                    server_window: WINDOW,
+                    closed: false,
+                    waker: None,
+                },
+            );
+
+            // the SYN is sent along with the first packets of the session
+            guard.queue_packet(sid, Flags::SYN, &[]);
+
+            sid
+        };
+
+        Self {
+            mux,
+            sid,
+            rbuf: Bytes::new(),
+            wbuf: Vec::new(),
+            wpos: 0,
+            fin_sent: false,
+        }
+    }
+
+    fn poll_close_session(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut mux = lock(&self.mux);
+
+        if !self.fin_sent {
+            mux.queue_packet(self.sid, Flags::FIN, &[]);
+            self.fin_sent = true;
+        }
+
+        // the socket itself is closed when the last session is dropped
+        mux.poll_flush_socket(cx)
+    }
+}
+
+impl<S> AsyncRead for SmpSession<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut PollReadBuf<'_>,
+    ) -> Poll<io::Result<PollReadOut>> {
+        let this = &mut *self;
+
+        if this.rbuf.is_empty() {
+            let mut mux = lock(&this.mux);
+
+            loop {
+                let session = mux
+                    .sessions
+                    .get_mut(&this.sid)
+                    .ok_or(io::ErrorKind::NotConnected)?;
+
+                if let Some(data) = session.received.pop_front() {
+                    // make room for another packet and let the server know
+                    session.window = session.window.wrapping_add(1);
+                    mux.queue_packet(this.sid, Flags::ACK, &[]);
+
+                    if let Poll::Ready(Err(error)) = mux.poll_flush_socket(cx) {
+                        return Poll::Ready(Err(error));
+                    }
+
+                    this.rbuf = data;
+                    break;
+                }
+
+                if session.closed {
+                    // EOF
+                    #[cfg(feature = "_rt-tokio")]
+                    return Poll::Ready(Ok(()));
+
+                    #[cfg(feature = "_rt-async-std")]
+                    return Poll::Ready(Ok(0));
+                }
+
+                session.waker = Some(cx.waker().clone());
+
+                // send any ACKs that could not be written before
+                if let Poll::Ready(Err(error)) = mux.poll_flush_socket(cx) {
+                    return Poll::Ready(Err(error));
+                }
+
+                ready!(mux.poll_recv(cx))?;
+            }
+        }
+
+        #[cfg(feature = "_rt-tokio")]
+        {
+            let n = cmp::min(buf.remaining(), this.rbuf.len());
+
+            buf.put_slice(&this.rbuf[..n]);
+            this.rbuf.advance(n);
+
+            Poll::Ready(Ok(()))
+        }
+
+        #[cfg(feature = "_rt-async-std")]
+        {
+            let n = cmp::min(buf.len(), this.rbuf.len());
+
+            buf[..n].copy_from_slice(&this.rbuf[..n]);
+            this.rbuf.advance(n);
+
+            Poll::Ready(Ok(n))
+        }
+    }
+}
+
+impl<S> AsyncWrite for SmpSession<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.wbuf.extend_from_slice(buf);
+
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+        let mut mux = lock(&this.mux);
+
+        // each TDS packet is sent in its own DATA packet
+        while this.wpos < this.wbuf.len() {
+            let session = mux
+                .sessions
+                .get_mut(&this.sid)
+                .ok_or(io::ErrorKind::NotConnected)?;
+
+            let next_seq_num = session.seq_num.wrapping_add(1);
+
+            if (session.server_window.wrapping_sub(next_seq_num) as i32) < 0 {
+                // the server is not ready for more packets; wait for its ACK
+                session.waker = Some(cx.waker().clone());
+
+                ready!(mux.poll_flush_socket(cx))?;
+                ready!(mux.poll_recv(cx))?;
+
+                continue;
+            }
+
+            let packet = &this.wbuf[this.wpos..];
+
+            // the length of a TDS packet is in bytes 2..4 of its header
+            let len = match packet.get(2..4) {
+                Some(len) => cmp::min(u16::from_be_bytes([len[0], len[1]]) as usize, packet.len()),
+                None => packet.len(),
+            };
+
+            mux.queue_packet(this.sid, Flags::DATA, &packet[..len]);
+            this.wpos += len;
+        }
+
+        this.wbuf.clear();
+        this.wpos = 0;
+
+        mux.poll_flush_socket(cx)
+    }
+
+    #[cfg(feature = "_rt-tokio")]
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_close_session(cx)
+    }
+
+    #[cfg(feature = "_rt-async-std")]
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_close_session(cx)
+    }
+}
+
+impl<S> Drop for SmpSession<S> {
+    fn drop(&mut self) {
+        let mut mux = lock(&self.mux);
+
+        if !self.fin_sent {
+            // the FIN is sent with the next packets of another session, if there is one
+            if let Some(session) = mux.sessions.get(&self.sid) {
+                let header = Header {
+                    flags: Flags::FIN,
+                    sid: self.sid,
+                    length: HEADER_BYTES as u32,
+                    seq_num: session.seq_num,
+                    window: session.window,
+                };
+
+                header.put(&mut mux.wbuf);
+            }
+        }
+
+        mux.sessions.remove(&self.sid);
+
+        // this session may have been the last to poll the socket
+        mux.wake_all();
+    }
+}
+
+fn lock<S>(mux: &Mutex<Mux<S>>) -> MutexGuard<'_, Mux<S>> {
+    mux.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[test]
+fn test_header() {
+    let mut buf = Vec::new();
+
+    Header {
+        flags: Flags::DATA,
+        sid: 1,
+        length: 24,
+        seq_num: 2,
+        window: 5,
+    }
+    .put(&mut buf);
+
+    #[rustfmt::skip]
+    assert_eq!(buf, [
+        0x53, 0x08, 0x01, 0x00, 0x18, 0x00, 0x00, 0x00,
+        0x02, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00,
+    ]);
+
+    let header = Header::get(&buf[..].try_into().unwrap()).unwrap();
+
+    assert_eq!(header.flags, Flags::DATA);
+    assert_eq!(header.sid, 1);
+    assert_eq!(header.length, 24);
+    assert_eq!(header.seq_num, 2);
+    assert_eq!(header.window, 5);
+
+    buf[0] = 0x12;
+    assert!(Header::get(&buf[..].try_into().unwrap()).is_err());
+}
@@ -1,15 +1,20 @@
+use std::io;
 use std::ops::{Deref, DerefMut};
 
 use bytes::{Bytes, BytesMut};
-use sqlx_rt::TcpStream;
+use sqlx_rt::{AsyncWriteExt, TcpStream};
 
 use crate::error::Error;
 use crate::ext::ustr::UStr;
 use crate::io::{BufStream, Encode};
+use crate::mssql::connection::browser;
+use crate::mssql::connection::smp::SmpStream;
+use crate::mssql::connection::tls_prelogin_stream_wrapper::TlsPreloginWrapper;
 use crate::mssql::protocol::col_meta_data::ColMetaData;
 use crate::mssql::protocol::done::{Done, Status as DoneStatus};
 use crate::mssql::protocol::env_change::EnvChange;
 use crate::mssql::protocol::error::Error as ProtocolError;
+use crate::mssql::protocol::feature_ext_ack::FeatureExtAck;
 use crate::mssql::protocol::info::Info;
 use crate::mssql::protocol::login_ack::LoginAck;
 use crate::mssql::protocol::message::{Message, MessageType};
@@ -18,22 +23,47 @@ use crate::mssql::protocol::packet::{PacketHeader, PacketType, Status};
 use crate::mssql::protocol::return_status::ReturnStatus;
 use crate::mssql::protocol::return_value::ReturnValue;
 use crate::mssql::protocol::row::Row;
+use crate::mssql::protocol::type_info::Collation;
 use crate::mssql::{MssqlColumn, MssqlConnectOptions, MssqlDatabaseError};
 use crate::net::MaybeTlsStream;
 use crate::HashMap;
 use std::sync::Arc;
 
+type Socket = MaybeTlsStream<TlsPreloginWrapper<TcpStream>>;
+
 pub(crate) struct MssqlStream {
-    inner: BufStream<MaybeTlsStream<TcpStream>>,
+    inner: BufStream<SmpStream<Socket>>,
 
     // how many Done (or Error) we are currently waiting for
     pub(crate) pending_done_count: usize,
 
+    // set while the results of a request are being read; if this is still set when the next
+    // request is made, the future or stream that was reading those results has been dropped
+    pub(crate) request_in_flight: bool,
+
+    // the number of bytes at the start of the write buffer that belong to the request in
+    // flight, which were not written yet because the write was interrupted
+    request_unsent: usize,
+
+    // set while a request is sent in several parts (e.g. a bulk load), to the type of the
+    // packets that are still to come
+    request_incomplete: Option<PacketType>,
+
+    // an ATTENTION was sent for the request in flight, but not yet acknowledged
+    attention_sent: bool,
+
     // current transaction descriptor
     // set from ENVCHANGE on `BEGIN` and reset to `0` on a ROLLBACK
     pub(crate) transaction_descriptor: u64,
     pub(crate) transaction_depth: usize,
 
+    // the isolation level of the session was changed by the active transaction
+    pub(crate) isolation_level_changed: bool,
+
+    // collation of the current database
+    // set from ENVCHANGE when logging in and on `USE`
+    pub(crate) collation: Option<Collation>,
+
     // current TabularResult from the server that we are iterating over
     response: Option<(PacketHeader, Bytes)>,
 
@@ -47,9 +77,31 @@ pub(crate) struct MssqlStream {
 
 impl MssqlStream {
     pub(super) async fn connect(options: &MssqlConnectOptions) -> Result<Self, Error> {
-        let inner = BufStream::new(MaybeTlsStream::Raw(
-            TcpStream::connect((&*options.host, options.port)).await?,
-        ));
+        let port = match &options.instance {
+            Some(instance) => {
+                match browser::resolve_instance_port(&options.host, instance).await {
+                    Ok(port) => port,
+
+                    Err(error) => {
+                        log::warn!(
+                            "failed to resolve the port of instance {:?} with the SQL Server Browser, \
+                             falling back to port {}: {}",
+                            instance,
+                            options.port,
+                            error
+                        );
+
+                        options.port
+                    }
+                }
+            }
+
+            None => options.port,
+        };
+
+        let inner = BufStream::new(SmpStream::Direct(MaybeTlsStream::Raw(
+            TlsPreloginWrapper::new(TcpStream::connect((&*options.host, port)).await?),
+        )));
 
         Ok(Self {
             inner,
@@ -57,13 +109,84 @@ impl MssqlStream {
             column_names: Default::default(),
             response: None,
             pending_done_count: 0,
+            request_in_flight: false,
+            request_unsent: 0,
+            request_incomplete: None,
+            attention_sent: false,
             transaction_descriptor: 0,
             transaction_depth: 0,
+            isolation_level_changed: false,
+            collation: None,
             packet_size : 8192,
             packet_id : 0
         })
     }
 
+    // from here on, everything is sent within the first session of a MARS connection
+    pub(super) fn start_mars(&mut self) {
+        self.inner.stream.start_mars();
+    }
+
+    // opens another session on a MARS connection
+    pub(super) fn open_session(&self) -> Result<Self, Error> {
+        let session = self.inner.stream.open_session().ok_or_else(|| {
+            Error::Configuration(
+                "Multiple Active Result Sets (MARS) is not enabled on this connection".into(),
+            )
+        })?;
+
+        Ok(Self {
+            inner: BufStream::new(SmpStream::Session(session)),
+            columns: Default::default(),
+            column_names: Default::default(),
+            response: None,
+            pending_done_count: 0,
+            request_in_flight: false,
+            request_unsent: 0,
+            request_incomplete: None,
+            attention_sent: false,
+            transaction_descriptor: 0,
+            transaction_depth: 0,
+            isolation_level_changed: false,
+            collation: self.collation,
+            packet_size: self.packet_size,
+            packet_id: 0,
+        })
+    }
+
+    // the socket itself, which is not reachable once MARS is started
+    pub(super) fn socket_mut(&mut self) -> Result<&mut Socket, Error> {
+        Ok(self.inner.stream.socket_mut()?)
+    }
+
+    // closes the session, or the connection if MARS is not in use
+    pub(super) async fn shutdown(&mut self) -> Result<(), Error> {
+        self.flush().await?;
+
+        #[cfg(feature = "_rt-async-std")]
+        {
+            use sqlx_rt::AsyncWrite;
+            use std::pin::Pin;
+
+            if let SmpStream::Direct(socket) = &mut self.inner.stream {
+                socket.shutdown(std::net::Shutdown::Both)?;
+            } else {
+                // the socket is left open for the other sessions
+                let stream = &mut self.inner.stream;
+                futures_util::future::poll_fn(|cx| Pin::new(&mut *stream).poll_close(cx)).await?;
+            }
+        }
+
+        #[cfg(feature = "_rt-tokio")]
+        {
+            // FIXME: This is equivalent to Shutdown::Write, not Shutdown::Both like above
+            // https://docs.rs/tokio/1.0.1/tokio/io/trait.AsyncWriteExt.html#method.shutdown
+            self.inner.stream.shutdown().await?;
+        }
+
+        Ok(())
+    }
+
     pub(crate) fn new_packet_id(&mut self) -> u8 {
         self.packet_id = self.packet_id.wrapping_add(1);
         return self.packet_id;
@@ -104,45 +227,82 @@ impl MssqlStream {
     pub(crate) async fn write_packet<'en, T: Encode<'en>>(&mut self, header: PacketHeader, payload: T)->Result<(), Error> {
         let mut payload_buf = Vec::<u8>::new();
         payload.encode(&mut payload_buf);
-        let packet_size = self.packet_size - 8;        
-        while !payload_buf.is_empty(){
-            let writable = std::cmp::min(packet_size, payload_buf.len());
-            let payload_split = payload_buf.split_off(writable);
+
+        self.write_packets(header, &payload_buf, true).await
+    }
+
+    // splits the payload into packets and writes them out
+    //
+    // if this is not the end of the message, the payload must fill a whole number of packets
+    // and the message must be completed by another call before anything else can be sent
+    pub(crate) async fn write_packets(
+        &mut self,
+        header: PacketHeader,
+        payload: &[u8],
+        end_of_message: bool,
+    ) -> Result<(), Error> {
+        let packet_size = self.packet_size - 8;
+        let mut chunks = payload.chunks(packet_size).peekable();
+
+        while let Some(chunk) = chunks.next() {
             let mut header1 = header.clone();
-            if payload_split.is_empty(){
-                header1.status = Status::END_OF_MESSAGE;                    
-            }
-            else {
-                header1.status = Status::NORMAL;
-            }
-            
-            self.write_packet1(
-                header1,
-                &payload_buf,
-                );
-            payload_buf = payload_split;            
-            self.flush().await?;           
-        }        
-        Ok(())
+
+            header1.status = if end_of_message && chunks.peek().is_none() {
+                Status::END_OF_MESSAGE
+            } else {
+                Status::NORMAL
+            };
+
+            self.write_packet1(header1, chunk);
+        }
+
+        self.request_incomplete = if end_of_message {
+            None
+        } else {
+            Some(header.r#type)
+        };
+
+        if self.request_in_flight {
+            // everything in the write buffer is part of the request
+            self.request_unsent = self.inner.wbuf.len();
+        }
+
+        self.flush().await
     }
 
     // writes the packet out to the write buffer
     // will (eventually) handle packet chunking
     pub(crate) fn write_packet1(&mut self, header: PacketHeader, payload: &[u8]) {
+        put_packet(&mut self.inner.wbuf, header, payload);
+    }
 
-        // write out the packet header, leaving room for setting the packet length later       
-        let mut len_offset = 0;
-        self.inner.write_with(
-            header,
-            &mut len_offset,
-        );
+    // writes out the write buffer
+    //
+    // unlike `BufStream::flush`, the bytes that were not written yet are kept in the buffer if
+    // this is interrupted, so that a request is never left half-sent
+    pub(crate) async fn flush(&mut self) -> Result<(), Error> {
+        self.write_out(self.inner.wbuf.len()).await
+    }
 
-        // write out the payload
-        self.inner.write(payload);
+    // writes out the first `len` bytes of the write buffer
+    async fn write_out(&mut self, len: usize) -> Result<(), Error> {
+        let mut remaining = len;
 
-        // overwrite the packet length now that we know it
-        let len = self.inner.wbuf.len();
-        self.inner.wbuf[len_offset..(len_offset + 2)].copy_from_slice(&(len as u16).to_be_bytes());
+        while remaining > 0 {
+            let written = self.inner.stream.write(&self.inner.wbuf[..remaining]).await?;
+
+            if written == 0 {
+                return Err(io::Error::from(io::ErrorKind::WriteZero).into());
+            }
+
+            self.inner.wbuf.drain(..written);
+            self.request_unsent = self.request_unsent.saturating_sub(written);
+            remaining -= written;
+        }
+
+        self.inner.stream.flush().await?;
+
+        Ok(())
     }
 
     // receive the next packet from the database
@@ -202,6 +362,10 @@ impl MssqlStream {
                                 self.transaction_descriptor = 0;
                             }
 
+                            EnvChange::SqlCollation(mut collation) if collation.len() >= 5 => {
+                                self.collation = Some(Collation::get(&mut collation));
+                            }
+
                             EnvChange::PacketSize(size)=>{
                                 self.packet_size =  size.parse().unwrap_or(0);
                             }
@@ -216,6 +380,11 @@ impl MssqlStream {
                         continue;
                     }
 
+                    MessageType::FeatureExtAck => {
+                        let _ = FeatureExtAck::get(buf)?;
+                        continue;
+                    }
+
                     MessageType::Row => Message::Row(Row::get(buf, false, &self.columns)?),
                     MessageType::NbcRow => Message::Row(Row::get(buf, true, &self.columns)?),
                     MessageType::LoginAck => Message::LoginAck(LoginAck::get(buf)?),
@@ -253,14 +422,94 @@ impl MssqlStream {
 
     pub(crate) fn handle_done(&mut self, _done: &Done) {
         self.pending_done_count -= 1;
+        self.request_in_flight = false;
     }
 
     pub(crate) fn handle_error<T>(&mut self, error: ProtocolError) -> Result<T, Error> {
-        // NOTE: [error] is sent IN ADDITION TO [done]
+        // NOTE: [error] is sent IN ADDITION TO [done]; the error is returned to the reader of
+        //       the results, so the rest of them are drained by `wait_until_ready` without
+        //       cancelling the request
+        self.request_in_flight = false;
+
         Err(MssqlDatabaseError(error).into())
     }
 
+    // sends an ATTENTION for the request in flight and discards the rest of its results
+    pub(crate) async fn cancel(&mut self) -> Result<(), Error> {
+        if !self.request_in_flight && !self.attention_sent {
+            return Ok(());
+        }
+
+        if !self.attention_sent {
+            let mut packets = Vec::new();
+
+            if let Some(r#type) = self.request_incomplete.take() {
+                // the rest of the request will never be sent; end it with a packet that
+                // tells the server to ignore it
+                let header = PacketHeader {
+                    r#type,
+                    status: Status::END_OF_MESSAGE | Status::IGNORE_EVENT,
+                    length: 0,
+                    server_process_id: 0,
+                    packet_id: self.new_packet_id(),
+                };
+
+                put_packet(&mut packets, header, &[]);
+            }
+
+            let header = PacketHeader {
+                r#type: PacketType::AttentionSignal,
+                status: Status::END_OF_MESSAGE,
+                length: 0,
+                server_process_id: 0,
+                packet_id: self.new_packet_id(),
+            };
+
+            put_packet(&mut packets, header, &[]);
+
+            // anything queued *after* the request in flight (e.g. the ROLLBACK of a dropped
+            // transaction) must not be cancelled, so the ATTENTION goes right after the rest
+            // of the request, which must be on the wire in full before it
+            let end = self.request_unsent;
+
+            self.request_unsent += packets.len();
+            self.inner.wbuf.splice(end..end, packets);
+            self.attention_sent = true;
+        }
+
+        // the queued requests are held back until the ATTENTION is acknowledged
+        self.write_out(self.request_unsent).await?;
+
+        // the server acknowledges the ATTENTION with a DONE that has DONE_ATTN set, and
+        // will send nothing more for the cancelled request after that
+        loop {
+            match self.recv_message().await {
+                Ok(Message::Done(done) | Message::DoneProc(done) | Message::DoneInProc(done))
+                    if done.status.contains(DoneStatus::DONE_ATTN) =>
+                {
+                    break;
+                }
+
+                // errors from the cancelled request are discarded along with its results
+                Ok(_) | Err(Error::Database(_)) => {}
+
+                Err(error) => return Err(error),
+            }
+        }
+
+        self.pending_done_count -= 1;
+        self.request_in_flight = false;
+        self.attention_sent = false;
+
+        Ok(())
+    }
+
     pub(crate) async fn wait_until_ready(&mut self) -> Result<(), Error> {
+        if self.request_in_flight || self.attention_sent {
+            // the results of the last request were not read to completion
+            self.cancel().await?;
+        }
+
         if !self.wbuf.is_empty() {
             self.flush().await?;
         }
@@ -280,8 +529,24 @@ impl MssqlStream {
     }
 }
 
+// writes a packet to the buffer, setting its length
+fn put_packet(buf: &mut Vec<u8>, header: PacketHeader, payload: &[u8]) {
+    let start = buf.len();
+
+    // write out the packet header, leaving room for setting the packet length later
+    let mut len_offset = 0;
+    header.encode_with(buf, &mut len_offset);
+
+    // write out the payload
+    buf.extend_from_slice(payload);
+
+    // overwrite the packet length now that we know it
+    let len = buf.len() - start;
+    buf[len_offset..(len_offset + 2)].copy_from_slice(&(len as u16).to_be_bytes());
+}
+
 impl Deref for MssqlStream {
-    type Target = BufStream<MaybeTlsStream<TcpStream>>;
+    type Target = BufStream<SmpStream<Socket>>;
 
     fn deref(&self) -> &Self::Target {
         &self.inner
@@ -0,0 +1,73 @@
+use crate::error::Error;
+use crate::mssql::connection::stream::MssqlStream;
+use crate::mssql::protocol::pre_login::Encrypt;
+use crate::mssql::{MssqlConnectOptions, MssqlEncrypt};
+
+/// The encryption level we advertise in our PRELOGIN message.
+pub(super) fn client_encrypt(options: &MssqlConnectOptions) -> Encrypt {
+    match options.encrypt {
+        MssqlEncrypt::Disabled => Encrypt::NOT_SUPPORTED,
+        MssqlEncrypt::Preferred | MssqlEncrypt::Required => Encrypt::ON,
+    }
+}
+
+pub(super) async fn maybe_upgrade(
+    stream: &mut MssqlStream,
+    options: &MssqlConnectOptions,
+    server: Encrypt,
+) -> Result<(), Error> {
+    // https://docs.microsoft.com/en-us/openspecs/windows_protocols/ms-tds/60f56408-0188-4cd5-8b90-25c6f2423868
+    // ignore the CLIENT_CERT bit, we only care about the negotiated level
+    let server = Encrypt::from_bits_truncate(server.bits() & Encrypt::REQUIRED.bits());
+
+    match options.encrypt {
+        MssqlEncrypt::Disabled => {
+            if server == Encrypt::REQUIRED {
+                return Err(Error::Tls(
+                    "server requires encryption but `encrypt` is disabled".into(),
+                ));
+            }
+        }
+
+        MssqlEncrypt::Preferred => {
+            if server == Encrypt::ON || server == Encrypt::REQUIRED {
+                upgrade(stream, options).await?;
+            }
+        }
+
+        MssqlEncrypt::Required => {
+            if server != Encrypt::ON && server != Encrypt::REQUIRED {
+                // upgrade failed, die
+                return Err(Error::Tls("server does not support TLS".into()));
+            }
+
+            upgrade(stream, options).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn upgrade(stream: &mut MssqlStream, options: &MssqlConnectOptions) -> Result<(), Error> {
+    let accept_invalid_certs = options.trust_server_certificate;
+
+    // the TLS handshake travels inside of PRELOGIN packets
+    stream.socket_mut()?.start_handshake();
+
+    stream
+        .socket_mut()?
+        .upgrade(
+            &options.host,
+            accept_invalid_certs,
+            accept_invalid_certs,
+            options.ssl_root_cert.as_ref(),
+            None,
+            None,
+        )
+        .await?;
+
+    // from here on, TLS records are written directly to the socket
+    stream.socket_mut()?.handshake_complete();
+
+    Ok(())
+}
@@ -0,0 +1,233 @@
+use std::cmp;
+use std::io;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::ready;
+use sqlx_rt::{AsyncRead, AsyncWrite};
+
+use crate::io::Encode;
+use crate::mssql::protocol::packet::{PacketHeader, PacketType, Status};
+use crate::net::{PollReadBuf, PollReadOut};
+
+const HEADER_BYTES: usize = 8;
+
+// During the TLS handshake, every TLS record exchanged with the server must travel inside
+// the payload of a TDS PRELOGIN packet. Once the handshake is complete, TLS records are
+// written directly to the underlying stream.
+//
+// This wrapper sits *below* the TLS layer; it frames outgoing handshake data into PRELOGIN
+// packets on flush and strips the packet headers from incoming handshake data.
+//
+// https://docs.microsoft.com/en-us/openspecs/windows_protocols/ms-tds/60f56408-0188-4cd5-8b90-25c6f2423868
+pub(crate) struct TlsPreloginWrapper<S> {
+    stream: S,
+    pending_handshake: bool,
+
+    // packet header of the incoming PRELOGIN packet
+    header_buf: [u8; HEADER_BYTES],
+    header_pos: usize,
+
+    // bytes remaining in the payload of the incoming PRELOGIN packet
+    read_remaining: usize,
+
+    // outgoing PRELOGIN packet, including room for its header
+    wbuf: Vec<u8>,
+    wpos: usize,
+}
+
+impl<S> TlsPreloginWrapper<S> {
+    pub(crate) fn new(stream: S) -> Self {
+        Self {
+            stream,
+            pending_handshake: false,
+            header_buf: [0; HEADER_BYTES],
+            header_pos: 0,
+            read_remaining: 0,
+            wbuf: Vec::new(),
+            wpos: 0,
+        }
+    }
+
+    pub(crate) fn start_handshake(&mut self) {
+        self.pending_handshake = true;
+    }
+
+    pub(crate) fn handshake_complete(&mut self) {
+        self.pending_handshake = false;
+    }
+}
+
+impl<S> AsyncRead for TlsPreloginWrapper<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut PollReadBuf<'_>,
+    ) -> Poll<io::Result<PollReadOut>> {
+        let this = &mut *self;
+
+        if !this.pending_handshake {
+            return Pin::new(&mut this.stream).poll_read(cx, buf);
+        }
+
+        while this.read_remaining == 0 {
+            while this.header_pos < HEADER_BYTES {
+                let n = ready!(poll_read_slice(
+                    &mut this.stream,
+                    cx,
+                    &mut this.header_buf[this.header_pos..]
+                ))?;
+
+                if n == 0 {
+                    return Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()));
+                }
+
+                this.header_pos += n;
+            }
+
+            let length = u16::from_be_bytes([this.header_buf[2], this.header_buf[3]]) as usize;
+
+            this.header_pos = 0;
+            this.read_remaining = length.saturating_sub(HEADER_BYTES);
+        }
+
+        #[cfg(feature = "_rt-tokio")]
+        {
+            let max = cmp::min(buf.remaining(), this.read_remaining);
+            let n = ready!(poll_read_slice(
+                &mut this.stream,
+                cx,
+                buf.initialize_unfilled_to(max)
+            ))?;
+
+            buf.advance(n);
+
+            this.read_remaining -= n;
+
+            Poll::Ready(Ok(()))
+        }
+
+        #[cfg(feature = "_rt-async-std")]
+        {
+            let max = cmp::min(buf.len(), this.read_remaining);
+            let n = ready!(Pin::new(&mut this.stream).poll_read(cx, &mut buf[..max]))?;
+
+            this.read_remaining -= n;
+
+            Poll::Ready(Ok(n))
+        }
+    }
+}
+
+impl<S> AsyncWrite for TlsPreloginWrapper<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+
+        if !this.pending_handshake {
+            return Pin::new(&mut this.stream).poll_write(cx, buf);
+        }
+
+        if this.wbuf.is_empty() {
+            // leave room for the packet header, written when we flush
+            this.wbuf.resize(HEADER_BYTES, 0);
+        }
+
+        this.wbuf.extend_from_slice(buf);
+
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+
+        if this.pending_handshake && !this.wbuf.is_empty() {
+            if this.wpos == 0 {
+                let mut header = Vec::with_capacity(HEADER_BYTES);
+                let mut len_offset = 0;
+
+                PacketHeader {
+                    r#type: PacketType::PreLogin,
+                    status: Status::END_OF_MESSAGE,
+                    length: this.wbuf.len() as u16,
+                    server_process_id: 0,
+                    packet_id: 1,
+                }
+                .encode_with(&mut header, &mut len_offset);
+
+                this.wbuf[..HEADER_BYTES].copy_from_slice(&header);
+            }
+
+            while this.wpos < this.wbuf.len() {
+                let n = ready!(Pin::new(&mut this.stream).poll_write(cx, &this.wbuf[this.wpos..]))?;
+
+                if n == 0 {
+                    return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+                }
+
+                this.wpos += n;
+            }
+
+            this.wbuf.clear();
+            this.wpos = 0;
+        }
+
+        Pin::new(&mut this.stream).poll_flush(cx)
+    }
+
+    #[cfg(feature = "_rt-tokio")]
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+
+    #[cfg(feature = "_rt-async-std")]
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_close(cx)
+    }
+}
+
+pub(super) fn poll_read_slice<S>(
+    stream: &mut S,
+    cx: &mut Context<'_>,
+    buf: &mut [u8],
+) -> Poll<io::Result<usize>>
+where
+    S: AsyncRead + Unpin,
+{
+    #[cfg(feature = "_rt-tokio")]
+    {
+        let mut buf = sqlx_rt::ReadBuf::new(buf);
+        ready!(Pin::new(stream).poll_read(cx, &mut buf))?;
+
+        Poll::Ready(Ok(buf.filled().len()))
+    }
+
+    #[cfg(feature = "_rt-async-std")]
+    {
+        Pin::new(stream).poll_read(cx, buf)
+    }
+}
+
+impl<S> Deref for TlsPreloginWrapper<S> {
+    type Target = S;
+
+    fn deref(&self) -> &Self::Target {
+        &self.stream
+    }
+}
+
+impl<S> DerefMut for TlsPreloginWrapper<S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.stream
+    }
+}
@@ -1,7 +1,7 @@
 use std::error::Error as StdError;
 use std::fmt::{self, Debug, Display, Formatter};
 
-use crate::error::DatabaseError;
+use crate::error::{DatabaseError, ErrorKind};
 use crate::mssql::protocol::error::Error;
 
 /// An error returned from the MSSQL database.
@@ -35,6 +35,33 @@ impl DatabaseError for MssqlDatabaseError {
         &self.0.message
     }
 
+    fn is_retryable(&self) -> bool {
+        matches!(
+            self.0.number,
+            // chosen as the deadlock victim, the transaction was rolled back
+            1205
+            // update conflict of a snapshot isolation transaction
+            | 3960
+        )
+    }
+
+    fn kind(&self) -> ErrorKind {
+        // https://learn.microsoft.com/en-us/sql/relational-databases/errors-events/database-engine-events-and-errors
+        match self.0.number {
+            // violation of a unique constraint, or of a unique index
+            2627 | 2601 => ErrorKind::UniqueViolation,
+            // conflict with a foreign key or check constraint
+            547 => constraint_kind(&self.0.message),
+            // cannot insert NULL into a column
+            515 => ErrorKind::NotNullViolation,
+            1205 => ErrorKind::Deadlock,
+            3960 => ErrorKind::SerializationFailure,
+            // lock request timeout
+            1222 => ErrorKind::Timeout,
+            _ => ErrorKind::Other,
+        }
+    }
+
     #[doc(hidden)]
     fn as_error(&self) -> &(dyn StdError + Send + Sync + 'static) {
         self
@@ -50,3 +77,69 @@ impl DatabaseError for MssqlDatabaseError {
         self
     }
 }
+
+// The kind of constraint of error 547 is only part of its message:
+//
+//   The %ls statement conflicted with the %ls constraint "%.*ls". [..]
+//
+// Messages in other languages than English are not classified.
+fn constraint_kind(message: &str) -> ErrorKind {
+    let kind = message
+        .split_once(" statement conflicted with the ")
+        .and_then(|(_, rest)| rest.split_once(" constraint \""))
+        .map(|(kind, _)| kind);
+
+    match kind {
+        Some("CHECK") => ErrorKind::CheckViolation,
+
+        // `REFERENCE` when the referenced row is deleted or updated
+        Some("FOREIGN KEY" | "FOREIGN KEY SAME TABLE" | "REFERENCE") => {
+            ErrorKind::ForeignKeyViolation
+        }
+
+        _ => ErrorKind::Other,
+    }
+}
+
+#[test]
+fn test_constraint_kind() {
+    assert_eq!(
+        constraint_kind(
+            "The INSERT statement conflicted with the CHECK constraint \"CK_kinds_name\". \
+             The conflict occurred in database \"tempdb\", table \"dbo.#kinds\", column 'name'."
+        ),
+        ErrorKind::CheckViolation
+    );
+
+    assert_eq!(
+        constraint_kind(
+            "The INSERT statement conflicted with the FOREIGN KEY constraint \"FK_items_orders\". \
+             The conflict occurred in database \"sqlx\", table \"dbo.orders\", column 'id'."
+        ),
+        ErrorKind::ForeignKeyViolation
+    );
+
+    assert_eq!(
+        constraint_kind(
+            "The DELETE statement conflicted with the REFERENCE constraint \"FK_items_orders\". \
+             The conflict occurred in database \"sqlx\", table \"dbo.items\", column 'order_id'."
+        ),
+        ErrorKind::ForeignKeyViolation
+    );
+
+    // a CHECK constraint named after a foreign key
+    assert_eq!(
+        constraint_kind(
+            "The UPDATE statement conflicted with the CHECK constraint \"FOREIGN KEY\". \
+             The conflict occurred in database \"sqlx\", table \"dbo.items\"."
+        ),
+        ErrorKind::CheckViolation
+    );
+
+    assert_eq!(
+        constraint_kind(
+            "Die INSERT-Anweisung steht in Konflikt mit der CHECK-Einschränkung \"CK_kinds_name\"."
+        ),
+        ErrorKind::Other
+    );
+}
@@ -0,0 +1,353 @@
+use crate::connection::{ConnectOptions, Connection};
+use crate::error::Error;
+use crate::executor::Executor;
+use crate::migrate::MigrateError;
+use crate::migrate::{AppliedMigration, Migration};
+use crate::migrate::{Migrate, MigrateDatabase};
+use crate::mssql::{quote_identifier, Mssql, MssqlConnectOptions, MssqlConnection};
+use crate::query::query;
+use crate::query_as::query_as;
+use crate::query_scalar::query_scalar;
+use futures_core::future::BoxFuture;
+use std::str::FromStr;
+use std::time::Duration;
+use std::time::Instant;
+
+fn parse_for_maintenance(url: &str) -> Result<(MssqlConnectOptions, String), Error> {
+    let mut options = MssqlConnectOptions::from_str(url)?;
+
+    // pull out the name of the database to create
+    let database = options.database.clone();
+
+    if database.eq_ignore_ascii_case("master") {
+        return Err(Error::Configuration(
+            "DATABASE_URL must specify a database other than `master`".into(),
+        ));
+    }
+
+    // switch us to the maintenance database
+    options.database = "master".into();
+
+    Ok((options, database))
+}
+
+impl MigrateDatabase for Mssql {
+    fn create_database(url: &str) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            let (options, database) = parse_for_maintenance(url)?;
+            let mut conn = options.connect().await?;
+
+            let _ = conn
+                .execute(&*format!("CREATE DATABASE {}", quote_identifier(&database)))
+                .await?;
+
+            Ok(())
+        })
+    }
+
+    fn database_exists(url: &str) -> BoxFuture<'_, Result<bool, Error>> {
+        Box::pin(async move {
+            let (options, database) = parse_for_maintenance(url)?;
+            let mut conn = options.connect().await?;
+
+            let exists: bool =
+                query_scalar("SELECT CAST(CASE WHEN DB_ID(@p1) IS NULL THEN 0 ELSE 1 END AS BIT)")
+                    .bind(database)
+                    .fetch_one(&mut conn)
+                    .await?;
+
+            Ok(exists)
+        })
+    }
+
+    fn drop_database(url: &str) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            let (options, database) = parse_for_maintenance(url)?;
+            let mut conn = options.connect().await?;
+
+            let _ = conn
+                .execute(&*format!(
+                    "DROP DATABASE IF EXISTS {}",
+                    quote_identifier(&database)
+                ))
+                .await?;
+
+            Ok(())
+        })
+    }
+}
+
+impl Migrate for MssqlConnection {
+    fn ensure_migrations_table(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+        Box::pin(async move {
+            // language=TSQL
+            self.execute(
+                r#"
+IF OBJECT_ID(N'_sqlx_migrations', N'U') IS NULL
+CREATE TABLE _sqlx_migrations (
+    version BIGINT PRIMARY KEY,
+    description NVARCHAR(MAX) NOT NULL,
+    installed_on DATETIME2 NOT NULL DEFAULT SYSUTCDATETIME(),
+    success BIT NOT NULL,
+    checksum VARBINARY(MAX) NOT NULL,
+    execution_time BIGINT NOT NULL
+);
+                "#,
+            )
+            .await?;
+
+            Ok(())
+        })
+    }
+
+    fn version(&mut self) -> BoxFuture<'_, Result<Option<(i64, bool)>, MigrateError>> {
+        Box::pin(async move {
+            // language=TSQL
+            let row = query_as(
+                r#"
+SELECT TOP 1 version, CAST(CASE WHEN success = 1 THEN 0 ELSE 1 END AS BIT)
+FROM _sqlx_migrations
+ORDER BY version DESC
+                "#,
+            )
+            .fetch_optional(self)
+            .await?;
+
+            Ok(row)
+        })
+    }
+
+    fn dirty_version(&mut self) -> BoxFuture<'_, Result<Option<i64>, MigrateError>> {
+        Box::pin(async move {
+            // language=TSQL
+            let row: Option<(i64,)> = query_as(
+                "SELECT TOP 1 version FROM _sqlx_migrations WHERE success = 0 ORDER BY version",
+            )
+            .fetch_optional(self)
+            .await?;
+
+            Ok(row.map(|r| r.0))
+        })
+    }
+
+    fn list_applied_migrations(
+        &mut self,
+    ) -> BoxFuture<'_, Result<Vec<AppliedMigration>, MigrateError>> {
+        Box::pin(async move {
+            // language=TSQL
+            let rows: Vec<(i64, Vec<u8>)> =
+                query_as("SELECT version, checksum FROM _sqlx_migrations ORDER BY version")
+                    .fetch_all(self)
+                    .await?;
+
+            let migrations = rows
+                .into_iter()
+                .map(|(version, checksum)| AppliedMigration {
+                    version,
+                    checksum: checksum.into(),
+                })
+                .collect();
+
+            Ok(migrations)
+        })
+    }
+
+    fn lock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+        Box::pin(async move {
+            let database_name = current_database(self).await?;
+            let lock_id = generate_lock_id(&database_name);
+
+            // create an application lock over the database, owned by the session so that
+            // it outlives the transactions used to apply each migration
+            // this will not return until the lock is acquired
+
+            // https://docs.microsoft.com/en-us/sql/relational-databases/system-stored-procedures/sp-getapplock-transact-sql
+
+            // language=TSQL
+            let _ = query(
+                r#"
+DECLARE @result INT;
+EXEC @result = sp_getapplock @Resource = @p1, @LockMode = 'Exclusive', @LockOwner = 'Session', @LockTimeout = -1;
+IF @result < 0 THROW 50000, 'failed to acquire the migration lock', 1;
+                "#,
+            )
+            .bind(lock_id)
+            .execute(self)
+            .await?;
+
+            Ok(())
+        })
+    }
+
+    fn unlock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+        Box::pin(async move {
+            let database_name = current_database(self).await?;
+            let lock_id = generate_lock_id(&database_name);
+
+            // language=TSQL
+            let _ = query("EXEC sp_releaseapplock @Resource = @p1, @LockOwner = 'Session'")
+                .bind(lock_id)
+                .execute(self)
+                .await?;
+
+            Ok(())
+        })
+    }
+
+    fn validate<'e: 'm, 'm>(
+        &'e mut self,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(), MigrateError>> {
+        Box::pin(async move {
+            // language=TSQL
+            let checksum: Option<Vec<u8>> =
+                query_scalar("SELECT checksum FROM _sqlx_migrations WHERE version = @p1")
+                    .bind(migration.version)
+                    .fetch_optional(self)
+                    .await?;
+
+            match checksum {
+                Some(checksum) if checksum == &*migration.checksum => Ok(()),
+                Some(_) => Err(MigrateError::VersionMismatch(migration.version)),
+                None => Err(MigrateError::VersionMissing(migration.version)),
+            }
+        })
+    }
+
+    fn apply<'e: 'm, 'm>(
+        &'e mut self,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
+        Box::pin(async move {
+            let mut tx = self.begin().await?;
+            let start = Instant::now();
+
+            // Use a single transaction for the actual migration script and the essential bookeeping so we never
+            // execute migrations twice. See https://github.com/launchbadge/sqlx/issues/1966.
+            // The `execution_time` however can only be measured for the whole transaction. This value _only_ exists for
+            // data lineage and debugging reasons, so it is not super important if it is lost. So we initialize it to -1
+            // and update it once the actual transaction completed.
+            for batch in split_batches(&migration.sql) {
+                let _ = tx.execute(batch).await?;
+            }
+
+            // language=TSQL
+            let _ = query(
+                r#"
+    INSERT INTO _sqlx_migrations ( version, description, success, checksum, execution_time )
+    VALUES ( @p1, @p2, 1, @p3, -1 )
+                "#,
+            )
+            .bind(migration.version)
+            .bind(&*migration.description)
+            .bind(&*migration.checksum)
+            .execute(&mut tx)
+            .await?;
+
+            tx.commit().await?;
+
+            // Update `elapsed_time`.
+            // NOTE: The process may disconnect/die at this point, so the elapsed time value might be lost. We accept
+            //       this small risk since this value is not super important.
+
+            let elapsed = start.elapsed();
+
+            // language=TSQL
+            let _ = query(
+                r#"
+    UPDATE _sqlx_migrations
+    SET execution_time = @p1
+    WHERE version = @p2
+                "#,
+            )
+            .bind(elapsed.as_nanos() as i64)
+            .bind(migration.version)
+            .execute(self)
+            .await?;
+
+            Ok(elapsed)
+        })
+    }
+
+    fn revert<'e: 'm, 'm>(
+        &'e mut self,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
+        Box::pin(async move {
+            // Use a single transaction for the actual migration script and the essential bookeeping so we never
+            // execute migrations twice. See https://github.com/launchbadge/sqlx/issues/1966.
+            let mut tx = self.begin().await?;
+            let start = Instant::now();
+
+            for batch in split_batches(&migration.sql) {
+                let _ = tx.execute(batch).await?;
+            }
+
+            // language=TSQL
+            let _ = query(r#"DELETE FROM _sqlx_migrations WHERE version = @p1"#)
+                .bind(migration.version)
+                .execute(&mut tx)
+                .await?;
+
+            tx.commit().await?;
+
+            let elapsed = start.elapsed();
+
+            Ok(elapsed)
+        })
+    }
+}
+
+// Statements such as `CREATE PROCEDURE` must be the first statement in a batch, so migrations
+// are split into batches on `GO` lines, like `sqlcmd` does. The server does not understand `GO`.
+fn split_batches(sql: &str) -> Vec<&str> {
+    let mut batches = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+
+    for line in sql.split_inclusive('\n') {
+        if line.trim().eq_ignore_ascii_case("go") {
+            batches.push(&sql[start..offset]);
+            start = offset + line.len();
+        }
+
+        offset += line.len();
+    }
+
+    batches.push(&sql[start..]);
+    batches.retain(|batch| !batch.trim().is_empty());
+
+    batches
+}
+
+async fn current_database(conn: &mut MssqlConnection) -> Result<String, MigrateError> {
+    // language=TSQL
+    Ok(query_scalar("SELECT DB_NAME()").fetch_one(conn).await?)
+}
+
+// inspired from rails: https://github.com/rails/rails/blob/6e49cc77ab3d16c06e12f93158eaf3e507d4120e/activerecord/lib/active_record/migration.rb#L1308
+fn generate_lock_id(database_name: &str) -> String {
+    const CRC_IEEE: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
+    // 0x3d32ad9e chosen by fair dice roll
+    format!(
+        "{:x}",
+        0x3d32ad9e * (CRC_IEEE.checksum(database_name.as_bytes()) as i64)
+    )
+}
+
+#[test]
+fn test_split_batches() {
+    let sql =
+        "CREATE TABLE t (id INT);\nGO\nCREATE VIEW v AS SELECT id FROM t;\r\n  go  \r\n\nGO\n";
+
+    assert_eq!(
+        split_batches(sql),
+        [
+            "CREATE TABLE t (id INT);\n",
+            "CREATE VIEW v AS SELECT id FROM t;\r\n"
+        ]
+    );
+
+    // `GO` only separates batches on a line of its own
+    assert_eq!(split_batches("SELECT 1 AS go"), ["SELECT 1 AS go"]);
+}
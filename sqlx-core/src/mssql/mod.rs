@@ -18,12 +18,20 @@ mod type_info;
 pub mod types;
 mod value;
 
+#[cfg(feature = "migrate")]
+mod migrate;
+
+#[cfg(feature = "migrate")]
+mod testing;
+
 pub use arguments::MssqlArguments;
 pub use column::MssqlColumn;
-pub use connection::MssqlConnection;
+pub use connection::{
+    MssqlBulkLoad, MssqlBulkRow, MssqlConnection, MssqlProcedureCall, MssqlProcedureResult,
+};
 pub use database::Mssql;
 pub use error::MssqlDatabaseError;
-pub use options::MssqlConnectOptions;
+pub use options::{MssqlConnectOptions, MssqlEncrypt};
 pub use query_result::MssqlQueryResult;
 pub use row::MssqlRow;
 pub use statement::MssqlStatement;
@@ -49,3 +57,14 @@ impl_acquire!(Mssql, MssqlConnection);
 impl_column_index_for_row!(MssqlRow);
 impl_column_index_for_statement!(MssqlStatement);
 impl_into_maybe_pool!(Mssql, MssqlConnection);
+
+// quotes an identifier for use in T-SQL, e.g. `my]db` becomes `[my]]db]`
+pub(crate) fn quote_identifier(name: &str) -> String {
+    format!("[{}]", name.replace(']', "]]"))
+}
+
+#[test]
+fn test_quote_identifier() {
+    assert_eq!(quote_identifier("sqlx"), "[sqlx]");
+    assert_eq!(quote_identifier("sq]lx"), "[sq]]lx]");
+}
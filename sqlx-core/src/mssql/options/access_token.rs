@@ -0,0 +1,35 @@
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use futures_core::future::BoxFuture;
+
+use crate::error::Error;
+
+pub(crate) type AccessTokenCallback =
+    dyn Fn() -> BoxFuture<'static, Result<String, Error>> + Send + Sync + 'static;
+
+/// The source of the access token used for federated authentication.
+#[derive(Clone)]
+pub(crate) enum AccessToken {
+    Token(String),
+    Callback(Arc<AccessTokenCallback>),
+}
+
+impl AccessToken {
+    pub(crate) async fn get(&self) -> Result<String, Error> {
+        match self {
+            AccessToken::Token(token) => Ok(token.clone()),
+            AccessToken::Callback(callback) => callback().await,
+        }
+    }
+}
+
+impl Debug for AccessToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // NOTE: the token is a credential, so it is never printed
+        match self {
+            AccessToken::Token(_) => f.write_str("Token(..)"),
+            AccessToken::Callback(_) => f.write_str("Callback(..)"),
+        }
+    }
+}
@@ -3,6 +3,7 @@ use crate::error::Error;
 use crate::mssql::{MssqlConnectOptions, MssqlConnection};
 use futures_core::future::BoxFuture;
 use log::LevelFilter;
+use std::io;
 use std::time::Duration;
 
 impl ConnectOptions for MssqlConnectOptions {
@@ -12,7 +13,20 @@ impl ConnectOptions for MssqlConnectOptions {
     where
         Self::Connection: Sized,
     {
-        Box::pin(MssqlConnection::establish(self))
+        Box::pin(async move {
+            match self.connect_timeout {
+                Some(timeout) => sqlx_rt::timeout(timeout, MssqlConnection::establish(self))
+                    .await
+                    .map_err(|_| {
+                        Error::Io(io::Error::new(
+                            io::ErrorKind::TimedOut,
+                            "timed out while establishing a connection",
+                        ))
+                    })?,
+
+                None => MssqlConnection::establish(self).await,
+            }
+        })
     }
 
     fn log_statements(&mut self, level: LevelFilter) -> &mut Self {
@@ -0,0 +1,52 @@
+use crate::error::Error;
+use std::str::FromStr;
+
+/// Options for controlling the encryption negotiated with the server during PRELOGIN.
+///
+/// It is used by the [`encrypt`](super::MssqlConnectOptions::encrypt) method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MssqlEncrypt {
+    /// Never encrypt the connection. The connection will fail if the server requires encryption.
+    Disabled,
+
+    /// Encrypt the connection if the server supports it, otherwise continue in cleartext.
+    ///
+    /// The server certificate is verified unless
+    /// [`trust_server_certificate`](super::MssqlConnectOptions::trust_server_certificate)
+    /// is set.
+    Preferred,
+
+    /// Always encrypt the connection. The connection will fail if the server does not
+    /// support encryption.
+    ///
+    /// The server certificate is verified unless
+    /// [`trust_server_certificate`](super::MssqlConnectOptions::trust_server_certificate)
+    /// is set.
+    Required,
+}
+
+impl Default for MssqlEncrypt {
+    fn default() -> Self {
+        MssqlEncrypt::Preferred
+    }
+}
+
+impl FromStr for MssqlEncrypt {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match &*s.to_ascii_lowercase() {
+            "disabled" | "disable" | "not_supported" | "false" | "no" => MssqlEncrypt::Disabled,
+            "preferred" | "prefer" | "optional" => MssqlEncrypt::Preferred,
+            "required" | "require" | "mandatory" | "strict" | "true" | "yes" => {
+                MssqlEncrypt::Required
+            }
+
+            _ => {
+                return Err(Error::Configuration(
+                    format!("unknown value {:?} for `encrypt`", s).into(),
+                ));
+            }
+        })
+    }
+}
@@ -1,15 +1,73 @@
+use std::future::Future;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_core::future::BoxFuture;
+
 use crate::connection::LogSettings;
+use crate::error::Error;
+use crate::net::CertificateInput;
 
+mod access_token;
 mod connect;
+mod encrypt;
 mod parse;
 
+use access_token::AccessToken;
+pub use encrypt::MssqlEncrypt;
+
+// the packet sizes accepted by the server, in bytes
+pub(crate) const PACKET_SIZES: RangeInclusive<u32> = 512..=32767;
+
+/// Options and flags which can be used to configure a MSSQL connection.
+///
+/// A value of `MssqlConnectOptions` can be parsed from a connection URL:
+///
+/// ```text
+/// mssql://[user[:password]@]host[\instance][:port][/database][?param1=value1&...]
+/// ```
+///
+/// When a named instance is given, its port is discovered with the SQL Server Browser service
+/// on the host.
+///
+/// ## Parameters
+///
+/// |Parameter|Default|Description|
+/// |---------|-------|-----------|
+/// | `instance` | `None` | The name of the instance to connect to, in place of `host\instance`. |
+/// | `encrypt` | `preferred` | Determines whether or with what priority an encrypted connection will be negotiated. See [`MssqlEncrypt`]. |
+/// | `trust_server_certificate` | `false` | Skip verification of the server certificate when the connection is encrypted. |
+/// | `sslrootcert` | `None` | Sets the name of a file containing a list of trusted SSL Certificate Authorities. |
+/// | `application_name` | `None` | The name of the application, as reported by the server, e.g. in `sys.dm_exec_sessions`. |
+/// | `hostname` | `None` | The name of the client machine, as reported by the server. |
+/// | `language` | `None` | The language of the session, which overrides the default language of the login. |
+/// | `packet_size` | `8192` | The size of the TDS packets requested by the client, between 512 and 32767 bytes. |
+/// | `client_program_version` | `0` | The version of the client program, as reported by the server. |
+/// | `statement_cache_capacity` | `1024` | The maximum number of prepared statements stored in the cache. |
+/// | `connect_timeout` | `None` | The maximum number of seconds to wait for a connection to be established. |
+/// | `multiple_active_result_sets` | `false` | Enables Multiple Active Result Sets (MARS), see [`MssqlConnection::open_session`](crate::mssql::MssqlConnection::open_session). |
 #[derive(Debug, Clone)]
 pub struct MssqlConnectOptions {
     pub(crate) host: String,
     pub(crate) port: u16,
+    pub(crate) instance: Option<String>,
     pub(crate) username: String,
     pub(crate) database: String,
     pub(crate) password: Option<String>,
+    pub(crate) encrypt: MssqlEncrypt,
+    pub(crate) trust_server_certificate: bool,
+    pub(crate) ssl_root_cert: Option<CertificateInput>,
+    pub(crate) access_token: Option<AccessToken>,
+    pub(crate) application_name: Option<String>,
+    pub(crate) hostname: Option<String>,
+    pub(crate) language: Option<String>,
+    pub(crate) packet_size: u32,
+    pub(crate) client_program_version: u32,
+    pub(crate) statement_cache_capacity: usize,
+    pub(crate) connect_timeout: Option<Duration>,
+    pub(crate) multiple_active_result_sets: bool,
     pub(crate) log_settings: LogSettings,
 }
 
@@ -24,9 +82,22 @@ impl MssqlConnectOptions {
         Self {
             port: 1433,
             host: String::from("localhost"),
+            instance: None,
             database: String::from("master"),
             username: String::from("sa"),
             password: None,
+            encrypt: MssqlEncrypt::default(),
+            trust_server_certificate: false,
+            ssl_root_cert: None,
+            access_token: None,
+            application_name: None,
+            hostname: None,
+            language: None,
+            packet_size: 8192,
+            client_program_version: 0,
+            statement_cache_capacity: 1024,
+            connect_timeout: None,
+            multiple_active_result_sets: false,
             log_settings: Default::default(),
         }
     }
@@ -41,6 +112,25 @@ impl MssqlConnectOptions {
         self
     }
 
+    /// Sets the name of the instance to connect to, e.g. `SQLEXPRESS`.
+    ///
+    /// The port of the instance is discovered by asking the SQL Server Browser service on
+    /// the host over UDP port 1434. If the browser does not answer, the connection falls back
+    /// to the configured [`port`](Self::port).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mssql::MssqlConnectOptions;
+    /// let options = MssqlConnectOptions::new()
+    ///     .host("myserver")
+    ///     .instance("SQLEXPRESS");
+    /// ```
+    pub fn instance(mut self, instance: &str) -> Self {
+        self.instance = Some(instance.to_owned());
+        self
+    }
+
     pub fn username(mut self, username: &str) -> Self {
         self.username = username.to_owned();
         self
@@ -55,4 +145,189 @@ impl MssqlConnectOptions {
         self.database = database.to_owned();
         self
     }
+
+    /// Sets whether or with what priority an encrypted connection will be negotiated
+    /// with the server.
+    ///
+    /// By default, the mode is [`Preferred`](MssqlEncrypt::Preferred), and the client will
+    /// encrypt the connection if the server supports it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mssql::{MssqlEncrypt, MssqlConnectOptions};
+    /// let options = MssqlConnectOptions::new()
+    ///     .encrypt(MssqlEncrypt::Required);
+    /// ```
+    pub fn encrypt(mut self, encrypt: MssqlEncrypt) -> Self {
+        self.encrypt = encrypt;
+        self
+    }
+
+    /// Sets whether the server certificate should be accepted without verification
+    /// when the connection is encrypted.
+    ///
+    /// Defaults to `false`.
+    pub fn trust_server_certificate(mut self, trust: bool) -> Self {
+        self.trust_server_certificate = trust;
+        self
+    }
+
+    /// Sets the name of a file containing SSL certificate authority (CA) certificate(s).
+    /// If the file exists, the server's certificate will be verified to be signed by
+    /// one of these authorities.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mssql::{MssqlEncrypt, MssqlConnectOptions};
+    /// let options = MssqlConnectOptions::new()
+    ///     .encrypt(MssqlEncrypt::Required)
+    ///     .ssl_root_cert("./ca-certificate.crt");
+    /// ```
+    pub fn ssl_root_cert(mut self, cert: impl AsRef<Path>) -> Self {
+        self.ssl_root_cert = Some(CertificateInput::File(cert.as_ref().to_path_buf()));
+        self
+    }
+
+    /// Sets PEM encoded trusted SSL Certificate Authorities (CA).
+    pub fn ssl_root_cert_from_pem(mut self, pem_certificate: Vec<u8>) -> Self {
+        self.ssl_root_cert = Some(CertificateInput::Inline(pem_certificate));
+        self
+    }
+
+    /// Sets an access token to login with federated authentication, e.g. an OAuth token
+    /// for Azure SQL acquired from Azure Active Directory.
+    ///
+    /// The username and password are not sent when an access token is set.
+    ///
+    /// As access tokens expire, prefer [`access_token_callback`](Self::access_token_callback)
+    /// for options that will be used to open connections over a long time, such as for a pool.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mssql::{MssqlEncrypt, MssqlConnectOptions};
+    /// let options = MssqlConnectOptions::new()
+    ///     .host("myserver.database.windows.net")
+    ///     .database("mydb")
+    ///     .encrypt(MssqlEncrypt::Required)
+    ///     .access_token("eyJ0eXAiOi...");
+    /// ```
+    pub fn access_token(mut self, token: impl Into<String>) -> Self {
+        self.access_token = Some(AccessToken::Token(token.into()));
+        self
+    }
+
+    /// Sets a callback that provides the access token to login with federated authentication.
+    ///
+    /// The callback is invoked each time a new connection is established, so it can return
+    /// a cached token and refresh it before it expires.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::mssql::{MssqlEncrypt, MssqlConnectOptions};
+    /// # async fn fetch_token() -> Result<String, sqlx_core::error::Error> { unimplemented!() }
+    /// let options = MssqlConnectOptions::new()
+    ///     .host("myserver.database.windows.net")
+    ///     .database("mydb")
+    ///     .encrypt(MssqlEncrypt::Required)
+    ///     .access_token_callback(|| fetch_token());
+    /// ```
+    pub fn access_token_callback<F, Fut>(mut self, callback: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<String, Error>> + Send + 'static,
+    {
+        let callback =
+            move || -> BoxFuture<'static, Result<String, Error>> { Box::pin(callback()) };
+
+        self.access_token = Some(AccessToken::Callback(Arc::new(callback)));
+        self
+    }
+
+    /// Sets the application name, which is reported by the server, e.g. as `program_name`
+    /// in `sys.dm_exec_sessions`. Defaults to None
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mssql::MssqlConnectOptions;
+    /// let options = MssqlConnectOptions::new()
+    ///     .application_name("my-app");
+    /// ```
+    pub fn application_name(mut self, application_name: &str) -> Self {
+        self.application_name = Some(application_name.to_owned());
+        self
+    }
+
+    /// Sets the name of the client machine, which is reported by the server, e.g. as
+    /// `host_name` in `sys.dm_exec_sessions`. Defaults to None
+    pub fn hostname(mut self, hostname: &str) -> Self {
+        self.hostname = Some(hostname.to_owned());
+        self
+    }
+
+    /// Sets the language of the session, e.g. `us_english`, which overrides the default
+    /// language of the login. Defaults to None
+    pub fn language(mut self, language: &str) -> Self {
+        self.language = Some(language.to_owned());
+        self
+    }
+
+    /// Sets the size of the packets requested from the server, in bytes.
+    ///
+    /// The server may choose a different size. The default packet size is 8192 bytes, and
+    /// sizes outside of 512 to 32767 bytes are clamped to that range.
+    pub fn packet_size(mut self, size: u32) -> Self {
+        self.packet_size = size.clamp(*PACKET_SIZES.start(), *PACKET_SIZES.end());
+        self
+    }
+
+    /// Sets the version of the client program, which is reported by the server, e.g. as
+    /// `client_version` in `sys.dm_exec_sessions`. Defaults to 0
+    pub fn client_program_version(mut self, version: u32) -> Self {
+        self.client_program_version = version;
+        self
+    }
+
+    /// Sets the capacity of the connection's statement cache in a number of stored
+    /// distinct statements. Caching is handled using LRU, meaning when the
+    /// amount of queries hits the defined limit, the oldest statement will get
+    /// dropped.
+    ///
+    /// The default cache capacity is 1024 statements.
+    pub fn statement_cache_capacity(mut self, capacity: usize) -> Self {
+        self.statement_cache_capacity = capacity;
+        self
+    }
+
+    /// Sets the maximum amount of time to wait for a connection to be established,
+    /// including the login. Defaults to no timeout.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets whether Multiple Active Result Sets (MARS) should be requested from the server.
+    ///
+    /// With MARS, several requests can be active at once on the same connection, each in
+    /// its own session, e.g. to run updates while the results of a query are still being
+    /// read. Additional sessions are opened with
+    /// [`MssqlConnection::open_session`](crate::mssql::MssqlConnection::open_session).
+    ///
+    /// Defaults to `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mssql::MssqlConnectOptions;
+    /// let options = MssqlConnectOptions::new()
+    ///     .multiple_active_result_sets(true);
+    /// ```
+    pub fn multiple_active_result_sets(mut self, enabled: bool) -> Self {
+        self.multiple_active_result_sets = enabled;
+        self
+    }
 }
@@ -48,6 +48,9 @@ bitflags! {
         // for DoneRowCount or just an initialized variable.
         const DONE_COUNT = 0x0010;
 
+        // The DONE message is a server acknowledgement of a client ATTENTION message.
+        const DONE_ATTN = 0x0020;
+
         // Used in place of DONE_ERROR when an error occurred on the current SQL statement that is
         // severe enough to require the result set, if any, to be discarded.
         const DONE_SRVERROR = 0x0100;
@@ -0,0 +1,54 @@
+use bytes::{Buf, Bytes};
+
+use crate::error::Error;
+
+const TERMINATOR: u8 = 0xff;
+
+// The acknowledgement of the features requested in the FeatureExt block of LOGIN7,
+// sent by the server as part of the login response.
+#[allow(dead_code)]
+#[derive(Debug)]
+pub(crate) struct FeatureExtAck {
+    pub(crate) features: Vec<(u8, Bytes)>,
+}
+
+impl FeatureExtAck {
+    pub(crate) fn get(buf: &mut Bytes) -> Result<Self, Error> {
+        let mut features = Vec::new();
+
+        loop {
+            let feature_id = buf.get_u8();
+
+            if feature_id == TERMINATOR {
+                break;
+            }
+
+            let len = buf.get_u32_le() as usize;
+
+            if buf.remaining() < len {
+                return Err(err_protocol!(
+                    "FEATUREEXTACK: expected {} bytes of data for feature 0x{:02x}",
+                    len,
+                    feature_id
+                ));
+            }
+
+            features.push((feature_id, buf.split_to(len)));
+        }
+
+        Ok(Self { features })
+    }
+}
+
+#[test]
+fn test_get_feature_ext_ack() {
+    let mut buf = Bytes::from_static(&[0x02, 0, 0, 0, 0, 0x0a, 1, 0, 0, 0, 1, 0xff]);
+
+    let ack = FeatureExtAck::get(&mut buf).unwrap();
+
+    assert_eq!(ack.features.len(), 2);
+    assert_eq!(ack.features[0].0, 0x02);
+    assert!(ack.features[0].1.is_empty());
+    assert_eq!(&ack.features[1].1[..], &[1]);
+    assert!(buf.is_empty());
+}
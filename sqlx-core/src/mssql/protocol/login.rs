@@ -16,8 +16,22 @@ pub struct Login7<'a> {
     pub language: &'a str,
     pub database: &'a str,
     pub client_id: [u8; 6],
+
+    // an access token used to login with federated authentication (FEDAUTH), in place of
+    // the username and password
+    pub access_token: Option<&'a str>,
+
+    // whether the server responded with FEDAUTHREQUIRED in PRELOGIN
+    pub fed_auth_echo: bool,
 }
 
+// FeatureExt
+const FEATURE_FED_AUTH: u8 = 0x02;
+const FEATURE_TERMINATOR: u8 = 0xff;
+
+// FEDAUTH library used to acquire the token (bFedAuthLibrary)
+const FED_AUTH_LIBRARY_SECURITY_TOKEN: u8 = 0x01;
+
 impl Encode<'_> for Login7<'_> {
     fn encode_with(&self, buf: &mut Vec<u8>, _: ()) {
         // [Length] The total length of the LOGIN7 structure.
@@ -70,7 +84,11 @@ impl Encode<'_> for Login7<'_> {
         //    2 | <fUserInstance>
         //    1 | <fSendYukonBinaryXML>
         //    0 | <fChangePassword>
-        buf.push(0);
+        buf.push(if self.access_token.is_some() {
+            0b00_01_00_00
+        } else {
+            0
+        });
 
         // [ClientTimeZone] This field is not used and can be set to zero.
         buf.extend(&0_u32.to_le_bytes());
@@ -108,9 +126,20 @@ impl Encode<'_> for Login7<'_> {
         // [ServerName] The server name
         write_str(buf, &mut offsets, beg, self.server_name);
 
-        // [Extension] Points to an extension block.
-        // TODO: Implement to get FeatureExt which should let us use UTF-8
+        // [Extension] Points to an extension block, which holds the offset of FeatureExt
         write_offset(buf, &mut offsets, beg);
+
+        let feature_ext_offset = if self.access_token.is_some() {
+            buf[offsets..(offsets + 2)].copy_from_slice(&4_u16.to_le_bytes());
+
+            let pos = buf.len();
+            buf.extend(&0_u32.to_le_bytes());
+
+            Some(pos)
+        } else {
+            None
+        };
+
         offsets += 2;
 
         // [CltIntName] The interface library name
@@ -137,6 +166,18 @@ impl Encode<'_> for Login7<'_> {
         // [ChangePassword] New password for the specified login
         write_offset(buf, &mut offsets, beg);
 
+        // [FeatureExt] The requested features; this MUST be the last of the variable data
+        if let Some(pos) = feature_ext_offset {
+            let offset = (buf.len() - beg) as u32;
+            buf[pos..(pos + 4)].copy_from_slice(&offset.to_le_bytes());
+
+            if let Some(token) = self.access_token {
+                write_fed_auth(buf, token, self.fed_auth_echo);
+            }
+
+            buf.push(FEATURE_TERMINATOR);
+        }
+
         // Establish the length of the entire structure
         let len = buf.len();
         buf[beg..beg + 4].copy_from_slice(&((len - beg) as u32).to_le_bytes());
@@ -153,6 +194,30 @@ fn write_offset(buf: &mut Vec<u8>, offsets: &mut usize, beg: usize) {
     *offsets += 2;
 }
 
+fn write_fed_auth(buf: &mut Vec<u8>, token: &str, echo: bool) {
+    buf.push(FEATURE_FED_AUTH);
+
+    // [FeatureDataLen]
+    let len_pos = buf.len();
+    buf.extend(&0_u32.to_le_bytes());
+
+    // [Options]
+    //  7-1 | bFedAuthLibrary
+    //    0 | fFedAuthEcho
+    buf.push((FED_AUTH_LIBRARY_SECURITY_TOKEN << 1) | echo as u8);
+
+    // [FedAuthToken] The token, as UTF-16LE, prefixed by its length in bytes
+    let token_pos = buf.len();
+    buf.extend(&0_u32.to_le_bytes());
+    buf.put_utf16_str(token);
+
+    let token_len = (buf.len() - token_pos - 4) as u32;
+    buf[token_pos..(token_pos + 4)].copy_from_slice(&token_len.to_le_bytes());
+
+    let len = (buf.len() - len_pos - 4) as u32;
+    buf[len_pos..(len_pos + 4)].copy_from_slice(&len.to_le_bytes());
+}
+
 fn write_str(buf: &mut Vec<u8>, offsets: &mut usize, beg: usize, s: &str) {
     // Write the offset
     write_offset(buf, offsets, beg);
@@ -183,6 +248,8 @@ fn test_encode_login() {
         language: "",
         database: "",
         client_id: [0x00, 0x50, 0x8B, 0xE2, 0xB7, 0x8F],
+        access_token: None,
+        fed_auth_echo: false,
     };
 
     // Adapted from v20191101 of MS-TDS
@@ -241,3 +308,49 @@ fn test_encode_login() {
 
     assert_eq!(expected, buf);
 }
+
+#[test]
+fn test_encode_login_with_access_token() {
+    let mut buf = Vec::new();
+
+    let login = Login7 {
+        version: 0x74000004,
+        client_program_version: 0,
+        client_pid: 0,
+        packet_size: 0x1000,
+        hostname: "",
+        username: "",
+        password: "",
+        app_name: "",
+        server_name: "",
+        client_interface_name: "",
+        language: "",
+        database: "",
+        client_id: [0; 6],
+        access_token: Some("ab"),
+        fed_auth_echo: true,
+    };
+
+    login.encode(&mut buf);
+
+    // [OptionFlags3] fExtension
+    assert_eq!(buf[27], 0x10);
+
+    // [ibExtension] and [cbExtension]
+    assert_eq!(&buf[56..60], &[94, 0, 4, 0]);
+
+    // [ibFeatureExtLong]
+    assert_eq!(&buf[94..98], &[98, 0, 0, 0]);
+
+    #[rustfmt::skip]
+    assert_eq!(&buf[98..], &[
+        FEATURE_FED_AUTH,
+        9, 0, 0, 0,                 // [FeatureDataLen]
+        0x03,                       // [Options] SECURITYTOKEN, echo
+        4, 0, 0, 0,                 // [FedAuthTokenLen]
+        b'a', 0, b'b', 0,           // [FedAuthToken]
+        FEATURE_TERMINATOR,
+    ]);
+
+    assert_eq!(&buf[..4], &(buf.len() as u32).to_le_bytes());
+}
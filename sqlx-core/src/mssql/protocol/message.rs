@@ -24,6 +24,7 @@ pub(crate) enum MessageType {
     Info,
     LoginAck,
     EnvChange,
+    FeatureExtAck,
     Done,
     DoneProc,
     DoneInProc,
@@ -44,6 +45,7 @@ impl MessageType {
             0xab => MessageType::Info,
             0xac => MessageType::ReturnValue,
             0xad => MessageType::LoginAck,
+            0xae => MessageType::FeatureExtAck,
             0xd1 => MessageType::Row,
             0xd2 => MessageType::NbcRow,
             0xe3 => MessageType::EnvChange,
@@ -2,6 +2,7 @@ pub(crate) mod col_meta_data;
 pub(crate) mod done;
 pub(crate) mod env_change;
 pub(crate) mod error;
+pub(crate) mod feature_ext_ack;
 pub(crate) mod header;
 pub(crate) mod info;
 pub(crate) mod login;
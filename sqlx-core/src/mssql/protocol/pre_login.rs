@@ -18,18 +18,22 @@ pub(crate) struct PreLogin<'a> {
     pub(crate) thread_id: Option<u32>,
     pub(crate) trace_id: Option<TraceId>,
     pub(crate) multiple_active_result_sets: Option<bool>,
+
+    // whether federated authentication (e.g. an Azure AD access token) will be used
+    pub(crate) fed_auth_required: Option<bool>,
 }
 
 impl<'de> Decode<'de> for PreLogin<'de> {
     fn decode_with(buf: Bytes, _: ()) -> Result<Self, Error> {
         let mut version = None;
         let mut encryption = None;
+        let mut fed_auth_required = None;
+        let mut multiple_active_result_sets = None;
 
         // TODO: Decode the remainder of the structure
         // let mut instance = None;
         // let mut thread_id = None;
         // let mut trace_id = None;
-        // let mut multiple_active_result_sets = None;
 
         let mut offsets = buf.clone();
 
@@ -61,7 +65,16 @@ impl<'de> Decode<'de> for PreLogin<'de> {
                             encryption = Some(Encrypt::from_bits_truncate(data.get_u8()));
                         }
 
-                        tok => todo!("{:?}", tok),
+                        PreLoginOptionToken::FedAuthRequired => {
+                            fed_auth_required = Some(data.get_u8() != 0);
+                        }
+
+                        PreLoginOptionToken::MultipleActiveResultSets => {
+                            multiple_active_result_sets = Some(data.get_u8() != 0);
+                        }
+
+                        // TODO: Decode the remainder of the options
+                        _ => {}
                     }
                 }
 
@@ -89,6 +102,8 @@ impl<'de> Decode<'de> for PreLogin<'de> {
         Ok(Self {
             version,
             encryption,
+            fed_auth_required,
+            multiple_active_result_sets,
 
             ..Default::default()
         })
@@ -110,7 +125,8 @@ impl Encode<'_> for PreLogin<'_> {
             + self.instance.map_or(0, |_| 1)
             + self.thread_id.map_or(0, |_| 1)
             + self.trace_id.as_ref().map_or(0, |_| 1)
-            + self.multiple_active_result_sets.map_or(0, |_| 1);
+            + self.multiple_active_result_sets.map_or(0, |_| 1)
+            + self.fed_auth_required.map_or(0, |_| 1);
 
         // Calculate the length of the option offset block. Each block is 5 bytes and it ends in
         // a 1 byte terminator.
@@ -152,6 +168,11 @@ impl Encode<'_> for PreLogin<'_> {
             MultipleActiveResultSets.put(buf, &mut offsets, &mut offset, 1);
             buf.push(*mars as u8);
         }
+
+        if let Some(fed_auth) = self.fed_auth_required {
+            FedAuthRequired.put(buf, &mut offsets, &mut offset, 1);
+            buf.push(fed_auth as u8);
+        }
     }
 }
 
@@ -168,6 +189,9 @@ enum PreLoginOptionToken {
     MultipleActiveResultSets = 0x04,
 
     TraceId = 0x05,
+
+    // Federated authentication (FEDAUTHREQUIRED)
+    FedAuthRequired = 0x06,
 }
 
 impl PreLoginOptionToken {
@@ -192,6 +216,7 @@ impl PreLoginOptionToken {
             0x03 => PreLoginOptionToken::ThreadId,
             0x04 => PreLoginOptionToken::MultipleActiveResultSets,
             0x05 => PreLoginOptionToken::TraceId,
+            0x06 => PreLoginOptionToken::FedAuthRequired,
 
             _ => {
                 return None;
@@ -309,3 +334,17 @@ fn test_decode_pre_login() {
     // ENCRYPT_OFF
     assert_eq!(pre_login.encryption.bits(), 0);
 }
+
+#[test]
+fn test_decode_pre_login_fed_auth_required() {
+    #[rustfmt::skip]
+    let buffer = Bytes::from_static(&[
+        0, 0, 16, 0, 6, 1, 0, 22, 0, 1, 6, 0, 23, 0, 1, 255,
+        14, 0, 12, 209, 0, 0, 1, 1,
+    ]);
+
+    let pre_login = PreLogin::decode(buffer).unwrap();
+
+    assert_eq!(pre_login.encryption, Encrypt::ON);
+    assert_eq!(pre_login.fed_auth_required, Some(true));
+}
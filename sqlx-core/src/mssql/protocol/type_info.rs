@@ -1,10 +1,11 @@
 use bitflags::bitflags;
+use byteorder::{ByteOrder, LittleEndian};
 use bytes::{Buf, Bytes};
-use encoding_rs::Encoding;
+use encoding_rs::{EncoderResult, Encoding};
 
 use crate::encode::{Encode, IsNull};
 use crate::error::Error;
-use crate::mssql::io::MssqlBufExt;
+use crate::mssql::io::{MssqlBufExt, MssqlBufMutExt};
 use crate::mssql::Mssql;
 
 bitflags! {
@@ -16,6 +17,7 @@ bitflags! {
         const IGNORE_KANA = (1 << 3);
         const BINARY = (1 << 4);
         const BINARY2 = (1 << 5);
+        const UTF8 = (1 << 6);
     }
 }
 
@@ -85,6 +87,10 @@ pub(crate) enum DataType {
     Image = 0x22,
     NText = 0x63,
     Variant = 0x62,
+
+    // table-valued parameters, only ever sent by the client
+    // https://docs.microsoft.com/en-us/openspecs/windows_protocols/ms-tds/c264db71-c1ec-4fe8-b5ef-19d54b1e6566
+    Tvp = 0xf3,
 }
 
 // http://msdn.microsoft.com/en-us/library/dd358284.aspx
@@ -96,6 +102,9 @@ pub(crate) struct TypeInfo {
     pub(crate) scale: u8,
     pub(crate) precision: u8,
     pub(crate) collation: Option<Collation>,
+
+    // the name of the user-defined table type of a table-valued parameter
+    pub(crate) type_name: Option<String>,
 }
 
 impl TypeInfo {
@@ -106,26 +115,100 @@ impl TypeInfo {
             scale: 0,
             precision: 0,
             collation: None,
+            type_name: None,
         }
     }
 
-    pub(crate) fn encoding(&self) -> Result<&'static Encoding, Error> {
-        match self.ty {
-            DataType::NChar | DataType::NVarChar | DataType::NText => Ok(encoding_rs::UTF_16LE),
+    // the type of a column or parameter, as described by `sys.types` and the procedures
+    // `sp_describe_first_result_set` and `sp_describe_undeclared_parameters`
+    pub(crate) fn from_system_type(
+        system_type_id: i32,
+        max_length: i16,
+        precision: u8,
+        scale: u8,
+    ) -> Option<Self> {
+        // a `max_length` of -1 indicates a (MAX) type
+        let len = if max_length < 0 {
+            0xffff
+        } else {
+            max_length as u32
+        };
 
-            DataType::VarChar | DataType::Char | DataType::BigChar | DataType::BigVarChar => {
-                // unwrap: impossible to unwrap here, collation will be set
-                Ok(match self.collation.unwrap().locale {
-                    // This is the Western encoding for Windows. It is an extension of ISO-8859-1,
-                    // which is known as Latin 1.
-                    0x0409 => encoding_rs::WINDOWS_1252,
+        let time_size = match scale {
+            0..=2 => 3,
+            3 | 4 => 4,
+            _ => 5,
+        };
 
-                    locale => {
-                        return Err(err_protocol!("unsupported locale 0x{:?}", locale));
-                    }
-                })
+        let (ty, size) = match system_type_id {
+            34 => (DataType::Image, 0x7fff_ffff),
+            35 => (DataType::Text, 0x7fff_ffff),
+            36 => (DataType::Guid, 16),
+            40 => (DataType::DateN, 3),
+            41 => (DataType::TimeN, time_size),
+            42 => (DataType::DateTime2N, time_size + 3),
+            43 => (DataType::DateTimeOffsetN, time_size + 5),
+            48 => (DataType::IntN, 1),
+            52 => (DataType::IntN, 2),
+            56 => (DataType::IntN, 4),
+            58 => (DataType::DateTimeN, 4),
+            59 => (DataType::FloatN, 4),
+            60 => (DataType::MoneyN, 8),
+            61 => (DataType::DateTimeN, 8),
+            62 => (DataType::FloatN, 8),
+            99 => (DataType::NText, 0x7fff_ffff),
+            104 => (DataType::BitN, 1),
+            106 | 108 => (
+                if system_type_id == 106 {
+                    DataType::DecimalN
+                } else {
+                    DataType::NumericN
+                },
+                match precision {
+                    0..=9 => 5,
+                    10..=19 => 9,
+                    20..=28 => 13,
+                    _ => 17,
+                },
+            ),
+            122 => (DataType::MoneyN, 4),
+            127 => (DataType::IntN, 8),
+            165 => (DataType::BigVarBinary, len),
+            167 => (DataType::BigVarChar, len),
+            173 => (DataType::BigBinary, len),
+            175 => (DataType::BigChar, len),
+            231 => (DataType::NVarChar, len),
+            239 => (DataType::NChar, len),
+            241 => (DataType::Xml, 0xffff),
+
+            _ => return None,
+        };
+
+        Some(Self {
+            ty,
+            size,
+            scale,
+            precision,
+            collation: None,
+            type_name: None,
+        })
+    }
+
+    pub(crate) fn encoding(&self) -> Result<&'static Encoding, Error> {
+        match self.ty {
+            DataType::NChar | DataType::NVarChar | DataType::NText | DataType::Xml => {
+                Ok(encoding_rs::UTF_16LE)
             }
 
+            DataType::VarChar
+            | DataType::Char
+            | DataType::BigChar
+            | DataType::BigVarChar
+            | DataType::Text => match &self.collation {
+                Some(collation) => collation.encoding(),
+                None => Err(err_protocol!("missing collation for {} value", self.name())),
+            },
+
             _ => {
                 // default to UTF-8 for anything
                 // else coming in here
@@ -186,6 +269,7 @@ impl TypeInfo {
                     ty,
                     precision: 0,
                     collation: None,
+                    type_name: None,
                 }
             }
 
@@ -208,6 +292,7 @@ impl TypeInfo {
                     scale,
                     ty,
                     collation: None,
+                    type_name: None,
                 }
             }
 
@@ -236,6 +321,7 @@ impl TypeInfo {
                     collation: collation,
                     scale: 0,
                     precision: 0,
+                    type_name: None,
                 }
             }
             DataType::Char
@@ -254,15 +340,41 @@ impl TypeInfo {
                     collation: Some(collation),
                     scale: 0,
                     precision: 0,
+                    type_name: None,
                 }
             }
 
+            DataType::Xml => {
+                // XML_INFO; the schema collection bound to the column, if any, is not used
+                if buf.get_u8() == 1 {
+                    let _database = buf.get_b_varchar()?;
+                    let _owning_schema = buf.get_b_varchar()?;
+                    let _collection = buf.get_us_varchar()?;
+                }
+
+                Self::new(ty, 0xFFFF)
+            }
+
             _ => {
                 return Err(err_protocol!("unsupported data type {:?}", ty));
             }
         })
     }
 
+    // values of `varchar(max)`, `nvarchar(max)`, `varbinary(max)` and `xml` are sent as a
+    // partially length-prefixed (PLP) stream of chunks
+    pub(crate) fn is_plp(&self) -> bool {
+        match self.ty {
+            DataType::Xml => true,
+
+            DataType::BigVarChar | DataType::NVarChar | DataType::BigVarBinary => {
+                self.size == 0xFFFF
+            }
+
+            _ => false,
+        }
+    }
+
     // writes a TYPE_INFO to the buffer
     pub(crate) fn put(&self, buf: &mut Vec<u8>) {
         if self.ty == DataType::Image 
@@ -363,10 +475,26 @@ impl TypeInfo {
                 }
             }
 
-            DataType::BigVarChar
-            | DataType::BigChar
-            | DataType::NText            
-             => {
+            DataType::BigVarChar | DataType::BigChar => {
+                if self.size <= 8000 {
+                    buf.extend(8000_u16.to_le_bytes());
+                } else {
+                    buf.extend(&[0xff_u8; 2]);
+                }
+
+                if let Some(collation) = &self.collation {
+                    collation.put(buf);
+                } else {
+                    buf.extend(&0_u32.to_le_bytes());
+                    buf.push(0);
+                }
+
+                if self.size > 8000 {
+                    buf.extend(PLP_UNKNOWN_LEN.to_le_bytes().as_slice());
+                }
+            }
+
+            DataType::NText => {
                 buf.extend(&[0xff_u8; 2]);
                 // buf.extend(&[0u8; 5]);
                 if let Some(collation) = &self.collation {
@@ -381,17 +509,65 @@ impl TypeInfo {
                 buf.extend(0xfffffffffffffffe_u64.to_le_bytes().as_slice());
             }
 
+            DataType::Xml => {
+                // SCHEMA_PRESENT
+                buf.push(0);
+            }
+
+            DataType::Tvp => {
+                // TVP_TYPENAME
+                //  the database name must be empty; the schema is optional
+                let type_name = self.type_name.as_deref().unwrap_or_default();
+                let (schema, name) = match type_name.rsplit_once('.') {
+                    Some((schema, name)) => (schema, name),
+                    None => ("", type_name),
+                };
+
+                buf.put_b_varchar("");
+                buf.put_b_varchar(schema.trim_matches(|c| c == '[' || c == ']'));
+                buf.put_b_varchar(name.trim_matches(|c| c == '[' || c == ']'));
+            }
+
             _ => {
                 unimplemented!("unsupported data type {:?}", self.ty);
             }
         }
     }
 
+    // adjusts the type of a bound value so it can describe a column of a table-valued
+    // parameter or a bulk load, where every row must use the same TYPE_INFO
+    pub(crate) fn into_column_type(mut self) -> Self {
+        match self.ty {
+            DataType::BigBinary | DataType::BigVarBinary | DataType::Binary | DataType::VarBinary
+                if self.size == 0 =>
+            {
+                // values are sent with a short length, which limits columns to 8000 bytes
+                self.size = 8000;
+            }
+
+            DataType::NVarChar | DataType::NChar | DataType::BigVarChar | DataType::BigChar
+                if self.collation.is_none() =>
+            {
+                // use the same collation as a bound string parameter
+                self.collation = <&str as Encode<'_, Mssql>>::produces(&"")
+                    .and_then(|ty| ty.0.collation);
+            }
+
+            _ => {}
+        }
+
+        self
+    }
+
     pub(crate) fn is_null(&self) -> bool {
         matches!(self.ty, DataType::Null)
     }
 
     pub(crate) fn get_value(&self, buf: &mut Bytes) -> Option<Bytes> {
+        if self.is_plp() {
+            return get_plp_value(buf);
+        }
+
         match self.ty {
             DataType::Null
             | DataType::TinyInt
@@ -425,13 +601,7 @@ impl TypeInfo {
                 if size == 0 || size == 0xFF {
                     None
                 } else {
-                    let data = buf.split_to(size as usize);
-                    if self.ty == DataType::DateTimeOffsetN{
-                        println!("DateTimeOffsetN:{:?}", data);
-                        let slice = unsafe{std::slice::from_raw_parts(data.as_ptr(), data.len())};
-                        println!("DateTimeOffsetN: slice{:?}", slice);
-                    }
-                    Some(data)
+                    Some(buf.split_to(size as usize))
                 }
             }
 
@@ -459,55 +629,18 @@ impl TypeInfo {
                     Some(buf.split_to(size as usize))
                 }
             }
-            DataType::BigVarBinary
-            | DataType::BigBinary
-             => {
-                if self.size < 0xFFFF{
-
-                    let size = buf.get_u16_le();
+            DataType::BigVarBinary | DataType::BigBinary => {
+                let size = buf.get_u16_le();
 
-                    if size == 0xFF_FF {
-                        None
-                    } else {
-                        Some(buf.split_to(size as usize))
-                    }
-                }
-                else{
-                    let len = buf.get_u64_le();
-
-                    let mut data = match len {
-                        // NULL
-                        0xffffffffffffffff => return None,
-                        // Unknown size
-                        0xfffffffffffffffe => Vec::new(),
-                        // Known size
-                        _ => Vec::with_capacity(len as usize),
-                    };
-
-                    let mut chunk_data_left = 0;
-
-                    loop {
-                        if chunk_data_left == 0 {
-                            // We have no chunk. Start a new one.
-                            let chunk_size = buf.get_u32_le() as usize;
-
-                            if chunk_size == 0 {
-                                break; // found a sentinel, we're done
-                            } else {
-                                chunk_data_left = chunk_size
-                            }
-                        } else {
-                            // Just read a byte
-                            let byte = buf.get_u8();
-                            chunk_data_left -= 1;
-
-                            data.push(byte);
-                        }
-                    }
-                    Some(data.into())
+                if size == 0xFF_FF {
+                    None
+                } else {
+                    Some(buf.split_to(size as usize))
                 }
             }
 
+            DataType::Tvp => unreachable!("table-valued parameters are never sent by the server"),
+
             DataType::Text | DataType::Image | DataType::NText | DataType::Variant => {
                 let _text_pointer = buf.get_b_varbyte();
                 let size: u32;
@@ -586,17 +719,25 @@ impl TypeInfo {
                 }
             }
 
-            | DataType::Char
-            | DataType::VarChar
-            | DataType::BigVarChar
-            | DataType::BigChar
-            | DataType::Xml
-            | DataType::UserDefined => {
+            DataType::Char | DataType::VarChar | DataType::BigVarChar | DataType::BigChar => {
+                self.put_text_value(buf, value);
+            }
+
+            DataType::UserDefined => {
                 self.put_short_len_value(buf, value);
             }
 
+            DataType::Xml => {
+                self.put_plp_value(buf, value);
+            }
+
             DataType::Text | DataType::NText | DataType::Variant => {
                 self.put_long_len_value(buf, value);
+            }
+
+            DataType::Tvp => {
+                // the column metadata and rows are written out by the encoder
+                self.put_fixed_value(buf, value);
             }            
         }
     }
@@ -609,8 +750,9 @@ impl TypeInfo {
         let offset = buf.len();
         buf.push(0);
 
+        // a NULL is sent as a zero length for BYTELEN types
         let size = if let IsNull::Yes = value.encode(buf) {
-            0xFF
+            0
         } else {
             (buf.len() - offset - 1) as u8
         };
@@ -648,6 +790,65 @@ impl TypeInfo {
         buf[offset..(offset + 4)].copy_from_slice(&size.to_le_bytes());
     }
 
+    // The value is encoded as a string, which is sent in the code page of the collation.
+    //
+    // Characters that cannot be represented in the code page are replaced with `?`, as the
+    // server does when converting an `nvarchar` to a `varchar`.
+    pub(crate) fn put_text_value<'q, T: Encode<'q, Mssql>>(&self, buf: &mut Vec<u8>, value: T) {
+        // strings are encoded as UTF-16 for `nvarchar` or as UTF-8 for `varchar`
+        let source = match value.produces() {
+            Some(ty) if matches!(ty.0.ty, DataType::NVarChar | DataType::NChar) => {
+                encoding_rs::UTF_16LE
+            }
+
+            _ => encoding_rs::UTF_8,
+        };
+
+        let mut text = Vec::new();
+
+        let value = match value.encode(&mut text) {
+            IsNull::Yes => None,
+
+            IsNull::No => {
+                let text = source.decode_without_bom_handling(&text).0;
+                let encoding = self.encoding().unwrap_or(encoding_rs::WINDOWS_1252);
+
+                Some(encode_lossy(encoding, &text))
+            }
+        };
+
+        if self.size <= 8000 {
+            self.put_short_len_value(buf, value.as_deref());
+        } else {
+            self.put_long_len_value(buf, value.as_deref());
+        }
+    }
+
+    pub(crate) fn put_plp_value<'q, T: Encode<'q, Mssql>>(&self, buf: &mut Vec<u8>, value: T) {
+        let offset = buf.len();
+        buf.extend(&PLP_UNKNOWN_LEN.to_le_bytes());
+        buf.extend(&0_u32.to_le_bytes());
+
+        if let IsNull::Yes = value.encode(buf) {
+            buf.truncate(offset);
+            buf.extend(&PLP_NULL.to_le_bytes());
+
+            return;
+        }
+
+        // the value is sent as a single chunk, unless it is empty
+        let size = (buf.len() - offset - 12) as u32;
+
+        if size == 0 {
+            buf.truncate(offset + 8);
+        } else {
+            buf[(offset + 8)..(offset + 12)].copy_from_slice(&size.to_le_bytes());
+        }
+
+        // PLP_TERMINATOR
+        buf.extend(&0_u32.to_le_bytes());
+    }
+
 
     pub(crate) fn name(&self) -> &'static str {
         match self.ty {
@@ -701,6 +902,9 @@ impl TypeInfo {
             DataType::Image =>"IMAGE",
             DataType::Binary =>"BINARY",
             DataType::VarBinary =>"VARBINARY",
+            DataType::Tvp => "TABLE",
+            DataType::Guid => "UNIQUEIDENTIFIER",
+            DataType::Xml => "XML",
             _ => unimplemented!("name: unsupported data type {:?}", self.ty),
         }
     }
@@ -742,9 +946,9 @@ impl TypeInfo {
                 s.push_str(match self.ty {
                     DataType::VarChar => "varchar",
                     DataType::NVarChar => "nvarchar",
-                    DataType::BigVarChar => "bigvarchar",
+                    DataType::BigVarChar => "varchar",
                     DataType::Char => "char",
-                    DataType::BigChar => "bigchar",
+                    DataType::BigChar => "char",
                     DataType::NChar => "nchar",
 
                     _ => unreachable!(),
@@ -769,7 +973,7 @@ impl TypeInfo {
             }
 
             DataType::DateTime2N => {
-                s.push_str("datetime2");
+                s.push_str(&format!("datetime2({})", self.scale));
             }
 
             DataType::DateTimeN => {
@@ -792,10 +996,10 @@ impl TypeInfo {
                 s.push_str("numericn");
             }
             DataType::DateTimeOffsetN => {
-                s.push_str("datetimeoffset(7)");
+                s.push_str(&format!("datetimeoffset({})", self.scale));
             }
             DataType::TimeN => {
-                s.push_str("time(7)");                
+                s.push_str(&format!("time({})", self.scale));
             }
             DataType::BigVarBinary=>{
                 s.push_str("varbinary(MAX)");                
@@ -812,11 +1016,46 @@ impl TypeInfo {
             DataType::VarBinary=>{
                 s.push_str("varbinary");                
             }
+            DataType::Tvp => {
+                s.push_str(self.type_name.as_deref().unwrap_or_default());
+                s.push_str(" READONLY");
+            }
+            DataType::Guid => {
+                s.push_str("uniqueidentifier");
+            }
+            DataType::Xml => {
+                s.push_str("xml");
+            }
             _ => unimplemented!("fmt: unsupported data type {:?}", self.ty),
         }
     }
 }
 
+const PLP_NULL: u64 = 0xFFFF_FFFF_FFFF_FFFF;
+const PLP_UNKNOWN_LEN: u64 = 0xFFFF_FFFF_FFFF_FFFE;
+
+// reads a PLP value, leaving its chunks as they were sent so the value is not copied
+// (see `MssqlPlpReader`)
+fn get_plp_value(buf: &mut Bytes) -> Option<Bytes> {
+    if buf.get_u64_le() == PLP_NULL {
+        return None;
+    }
+
+    // PLP_CHUNK* PLP_TERMINATOR, where each chunk is prefixed by its length
+    let mut len = 0;
+
+    loop {
+        let chunk_len = LittleEndian::read_u32(&buf[len..]) as usize;
+        len += 4 + chunk_len;
+
+        if chunk_len == 0 {
+            break;
+        }
+    }
+
+    Some(buf.split_to(len))
+}
+
 impl DataType {
     pub(crate) fn get(buf: &mut Bytes) -> Result<Self, Error> {
         Ok(match buf.get_u8() {
@@ -871,6 +1110,97 @@ impl DataType {
 }
 
 impl Collation {
+    // The code page of `varchar`, `char` and `text` values with this collation
+    // https://docs.microsoft.com/en-us/sql/relational-databases/collations/collation-and-unicode-support
+    pub(crate) fn encoding(&self) -> Result<&'static Encoding, Error> {
+        if self.flags.contains(CollationFlags::UTF8) {
+            return Ok(encoding_rs::UTF_8);
+        }
+
+        // SQL Server collations (`SQL_*`) are identified by their sort order, Windows
+        // collations by their locale
+        if self.sort != 0 {
+            return Ok(match self.sort {
+                51..=54 | 183..=186 => encoding_rs::WINDOWS_1252,
+                80..=96 => encoding_rs::WINDOWS_1250,
+                104..=108 => encoding_rs::WINDOWS_1251,
+                112..=114 | 120..=124 => encoding_rs::WINDOWS_1253,
+                128..=130 => encoding_rs::WINDOWS_1254,
+                136..=138 => encoding_rs::WINDOWS_1255,
+                144..=146 => encoding_rs::WINDOWS_1256,
+                152..=160 => encoding_rs::WINDOWS_1257,
+
+                // code pages 437 and 850 (`SQL_Latin1_General_CP437_*` and
+                // `SQL_Latin1_General_CP850_*`) are not supported
+                sort => {
+                    return Err(err_protocol!("unsupported collation sort order {}", sort));
+                }
+            });
+        }
+
+        Ok(match self.locale & 0xffff {
+            // Chinese (PRC, Singapore)
+            0x0804 | 0x1004 => encoding_rs::GBK,
+
+            // Chinese (Taiwan, Hong Kong SAR, Macao SAR)
+            0x0404 | 0x0c04 | 0x1404 => encoding_rs::BIG5,
+
+            // Serbian (Cyrillic), Bosnian (Cyrillic)
+            0x0c1a | 0x1c1a | 0x201a | 0x301a => encoding_rs::WINDOWS_1251,
+
+            // Azeri (Cyrillic), Uzbek (Cyrillic)
+            0x082c | 0x0843 => encoding_rs::WINDOWS_1251,
+
+            // Azeri (Latin), Uzbek (Latin)
+            0x042c | 0x0443 => encoding_rs::WINDOWS_1254,
+
+            locale => match locale & 0x3ff {
+                // Japanese
+                0x11 => encoding_rs::SHIFT_JIS,
+
+                // Korean
+                0x12 => encoding_rs::EUC_KR,
+
+                // Thai
+                0x1e => encoding_rs::WINDOWS_874,
+
+                // Vietnamese
+                0x2a => encoding_rs::WINDOWS_1258,
+
+                // Central European: Czech, Croatian, Hungarian, Polish, Romanian, Slovak,
+                // Albanian, Slovenian, Turkmen
+                0x05 | 0x0e | 0x15 | 0x18 | 0x1a | 0x1b | 0x1c | 0x24 | 0x42 => {
+                    encoding_rs::WINDOWS_1250
+                }
+
+                // Cyrillic: Bulgarian, Russian, Ukrainian, Belarusian, Macedonian, Kazakh,
+                // Kyrgyz, Tatar, Mongolian, Bashkir, Yakut
+                0x02 | 0x19 | 0x22 | 0x23 | 0x2f | 0x3f | 0x40 | 0x44 | 0x50 | 0x6d | 0x85 => {
+                    encoding_rs::WINDOWS_1251
+                }
+
+                // Greek
+                0x08 => encoding_rs::WINDOWS_1253,
+
+                // Turkish
+                0x1f => encoding_rs::WINDOWS_1254,
+
+                // Hebrew
+                0x0d => encoding_rs::WINDOWS_1255,
+
+                // Arabic, Urdu, Farsi, Uyghur, Dari
+                0x01 | 0x20 | 0x29 | 0x80 | 0x8c => encoding_rs::WINDOWS_1256,
+
+                // Baltic: Estonian, Latvian, Lithuanian
+                0x25 | 0x26 | 0x27 => encoding_rs::WINDOWS_1257,
+
+                // This is the Western encoding for Windows. It is an extension of ISO-8859-1,
+                // which is known as Latin 1. Every other locale that supports `varchar` uses it.
+                _ => encoding_rs::WINDOWS_1252,
+            },
+        })
+    }
+
     pub(crate) fn get(buf: &mut Bytes) -> Collation {
         let locale_sort_version = buf.get_u32_le();
         let locale = locale_sort_version & 0xfffff;
@@ -894,3 +1224,111 @@ impl Collation {
         buf.push(self.sort);
     }
 }
+
+// encodes the text, replacing each character that cannot be represented with `?`
+pub(crate) fn encode_lossy(encoding: &'static Encoding, text: &str) -> Vec<u8> {
+    if encoding == encoding_rs::UTF_8 {
+        return text.as_bytes().to_vec();
+    }
+
+    let mut encoder = encoding.new_encoder();
+    let mut out = Vec::with_capacity(text.len());
+    let mut text = text;
+
+    loop {
+        let max_len = encoder
+            .max_buffer_length_from_utf8_without_replacement(text.len())
+            .unwrap_or(text.len() * 4);
+
+        out.reserve(max_len);
+
+        let (result, read) =
+            encoder.encode_from_utf8_to_vec_without_replacement(text, &mut out, true);
+
+        text = &text[read..];
+
+        match result {
+            EncoderResult::InputEmpty => return out,
+            EncoderResult::OutputFull => {}
+            EncoderResult::Unmappable(_) => out.push(b'?'),
+        }
+    }
+}
+
+#[test]
+fn test_collation_encoding() {
+    fn encoding(locale: u32, sort: u8) -> &'static Encoding {
+        Collation {
+            locale,
+            flags: CollationFlags::IGNORE_CASE,
+            sort,
+            version: 0,
+        }
+        .encoding()
+        .unwrap()
+    }
+
+    // SQL_Latin1_General_CP1_CI_AS
+    assert_eq!(encoding(0x0409, 52), encoding_rs::WINDOWS_1252);
+    // Latin1_General_CI_AS
+    assert_eq!(encoding(0x0409, 0), encoding_rs::WINDOWS_1252);
+    // Chinese_PRC_CI_AS
+    assert_eq!(encoding(0x0804, 0), encoding_rs::GBK);
+    // Chinese_Taiwan_Stroke_CI_AS
+    assert_eq!(encoding(0x0404, 0), encoding_rs::BIG5);
+    // Japanese_CI_AS
+    assert_eq!(encoding(0x0411, 0), encoding_rs::SHIFT_JIS);
+    // Cyrillic_General_CI_AS
+    assert_eq!(encoding(0x0419, 0), encoding_rs::WINDOWS_1251);
+    // Polish_CI_AS
+    assert_eq!(encoding(0x0415, 0), encoding_rs::WINDOWS_1250);
+    // SQL_Latin1_General_CP1250_CI_AS
+    assert_eq!(encoding(0x0409, 82), encoding_rs::WINDOWS_1250);
+
+    let utf8 = Collation {
+        locale: 0x0409,
+        flags: CollationFlags::IGNORE_CASE | CollationFlags::UTF8,
+        sort: 0,
+        version: 2,
+    };
+
+    assert_eq!(utf8.encoding().unwrap(), encoding_rs::UTF_8);
+
+    // SQL_Latin1_General_CP437_CI_AS
+    let cp437 = Collation {
+        locale: 0x0409,
+        flags: CollationFlags::IGNORE_CASE,
+        sort: 32,
+        version: 0,
+    };
+
+    assert!(cp437.encoding().is_err());
+}
+
+#[test]
+fn test_encode_lossy() {
+    assert_eq!(encode_lossy(encoding_rs::WINDOWS_1252, "café €"), b"caf\xe9 \x80");
+    assert_eq!(encode_lossy(encoding_rs::WINDOWS_1252, "中文"), b"??");
+    assert_eq!(encode_lossy(encoding_rs::GBK, "中文"), b"\xd6\xd0\xce\xc4");
+    assert_eq!(encode_lossy(encoding_rs::UTF_8, "中文"), "中文".as_bytes());
+}
+
+#[test]
+fn test_plp_value() {
+    let ty = TypeInfo::new(DataType::Xml, 0xFFFF);
+
+    let mut buf = Vec::new();
+    ty.put_plp_value(&mut buf, &b"<a/>"[..]);
+    ty.put_plp_value(&mut buf, &b""[..]);
+    ty.put_plp_value(&mut buf, Option::<&[u8]>::None);
+
+    let mut buf = Bytes::from(buf);
+
+    assert_eq!(
+        ty.get_value(&mut buf).as_deref(),
+        Some(&b"\x04\0\0\0<a/>\0\0\0\0"[..])
+    );
+    assert_eq!(ty.get_value(&mut buf).as_deref(), Some(&b"\0\0\0\0"[..]));
+    assert_eq!(ty.get_value(&mut buf), None);
+    assert!(buf.is_empty());
+}
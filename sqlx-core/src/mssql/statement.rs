@@ -18,6 +18,12 @@ pub struct MssqlStatement<'q> {
 pub(crate) struct MssqlStatementMetadata {
     pub(crate) columns: Vec<MssqlColumn>,
     pub(crate) column_names: HashMap<UStr, usize>,
+
+    // the declarations of the parameters, e.g. `@p1 int,@p2 nvarchar(50)`
+    pub(crate) declarations: Option<String>,
+
+    // the types of the positional parameters, if known
+    pub(crate) parameters: Option<Vec<MssqlTypeInfo>>,
 }
 
 impl<'q> Statement<'q> for MssqlStatement<'q> {
@@ -35,7 +41,7 @@ impl<'q> Statement<'q> for MssqlStatement<'q> {
     }
 
     fn parameters(&self) -> Option<Either<&[MssqlTypeInfo], usize>> {
-        None
+        self.metadata.parameters.as_deref().map(Either::Left)
     }
 
     fn columns(&self) -> &[MssqlColumn] {
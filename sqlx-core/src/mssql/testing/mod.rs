@@ -0,0 +1,231 @@
+use std::fmt::Write;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use futures_core::future::BoxFuture;
+
+use once_cell::sync::OnceCell;
+
+use crate::connection::Connection;
+
+use crate::error::Error;
+use crate::executor::Executor;
+use crate::mssql::{quote_identifier, Mssql, MssqlConnectOptions, MssqlConnection};
+use crate::pool::{Pool, PoolOptions};
+use crate::query::query;
+use crate::query_scalar::query_scalar;
+use crate::testing::{FixtureSnapshot, TestArgs, TestContext, TestSupport};
+
+// Using a blocking `OnceCell` here because the critical sections are short.
+static MASTER_POOL: OnceCell<Pool<Mssql>> = OnceCell::new();
+// Automatically delete any databases created before the start of the test binary.
+static DO_CLEANUP: AtomicBool = AtomicBool::new(true);
+
+impl TestSupport for Mssql {
+    fn test_context(args: &TestArgs) -> BoxFuture<'_, Result<TestContext<Self>, Error>> {
+        Box::pin(async move {
+            let res = test_context(args).await;
+            res
+        })
+    }
+
+    fn cleanup_test(db_name: &str) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            let mut conn = MASTER_POOL
+                .get()
+                .expect("cleanup_test() invoked outside `#[sqlx::test]")
+                .acquire()
+                .await?;
+
+            let db_id = db_id(db_name);
+
+            conn.execute(&drop_database(db_name)[..]).await?;
+
+            query("delete from _sqlx_test_databases where db_id = @p1")
+                .bind(db_id)
+                .execute(&mut conn)
+                .await?;
+
+            Ok(())
+        })
+    }
+
+    fn cleanup_test_dbs() -> BoxFuture<'static, Result<Option<usize>, Error>> {
+        Box::pin(async move {
+            let url = dotenvy::var("DATABASE_URL").expect("DATABASE_URL must be set");
+
+            let mut conn = MssqlConnection::connect(&url).await?;
+            let num_deleted = do_cleanup(&mut conn).await?;
+            let _ = conn.close().await;
+            Ok(Some(num_deleted))
+        })
+    }
+
+    fn snapshot(
+        _conn: &mut Self::Connection,
+    ) -> BoxFuture<'_, Result<FixtureSnapshot<Self>, Error>> {
+        // TODO: I want to get the testing feature out the door so this will have to wait,
+        // but I'm keeping the code around for now because I plan to come back to it.
+        todo!()
+    }
+}
+
+async fn test_context(args: &TestArgs) -> Result<TestContext<Mssql>, Error> {
+    let url = dotenvy::var("DATABASE_URL").expect("DATABASE_URL must be set");
+
+    let master_opts = MssqlConnectOptions::from_str(&url).expect("failed to parse DATABASE_URL");
+
+    let pool = PoolOptions::new()
+        // MSSQL allows up to 32767 user connections by default, but the server may have
+        // been configured with a lower limit and there may be fuzziness here due to
+        // concurrently running tests anyway.
+        .max_connections(20)
+        // Immediately close master connections. Tokio's I/O streams don't like hopping runtimes.
+        .after_release(|_conn, _| Box::pin(async move { Ok(false) }))
+        .connect_lazy_with(master_opts);
+
+    let master_pool = match MASTER_POOL.try_insert(pool) {
+        Ok(inserted) => inserted,
+        Err((existing, pool)) => {
+            // Sanity checks.
+            assert_eq!(
+                existing.connect_options().host,
+                pool.connect_options().host,
+                "DATABASE_URL changed at runtime, host differs"
+            );
+
+            assert_eq!(
+                existing.connect_options().database,
+                pool.connect_options().database,
+                "DATABASE_URL changed at runtime, database differs"
+            );
+
+            existing
+        }
+    };
+
+    let mut conn = master_pool.acquire().await?;
+
+    // language=TSQL
+    conn.execute(
+        // Concurrently running test binaries may race to create the table;
+        // error 2714 means someone else got there first.
+        r#"
+        begin try
+            if object_id(N'_sqlx_test_databases', N'U') is null
+            create table _sqlx_test_databases (
+                db_id bigint identity(1, 1) primary key,
+                test_path nvarchar(max) not null,
+                created_at datetime2 not null default sysutcdatetime()
+            );
+        end try
+        begin catch
+            if error_number() <> 2714 throw;
+        end catch
+    "#,
+    )
+    .await?;
+
+    // Only run cleanup if the test binary just started.
+    if DO_CLEANUP.swap(false, Ordering::SeqCst) {
+        do_cleanup(&mut conn).await?;
+    }
+
+    let new_db_id: i64 = query_scalar(
+        "insert into _sqlx_test_databases(test_path) output inserted.db_id values (@p1)",
+    )
+    .bind(&args.test_path)
+    .fetch_one(&mut conn)
+    .await?;
+
+    let new_db_name = db_name(new_db_id);
+
+    conn.execute(&format!("create database {}", quote_identifier(&new_db_name))[..])
+        .await?;
+
+    eprintln!("created database {}", new_db_name);
+
+    Ok(TestContext {
+        pool_opts: PoolOptions::new()
+            // Don't allow a single test to take all the connections.
+            // Most tests shouldn't require more than 5 connections concurrently,
+            // or else they're likely doing too much in one test.
+            .max_connections(5)
+            // Close connections ASAP if left in the idle queue.
+            .idle_timeout(Some(Duration::from_secs(1)))
+            .parent(master_pool.clone()),
+        connect_opts: master_pool.connect_options().clone().database(&new_db_name),
+        db_name: new_db_name,
+    })
+}
+
+async fn do_cleanup(conn: &mut MssqlConnection) -> Result<usize, Error> {
+    let delete_db_ids: Vec<i64> =
+        query_scalar("select db_id from _sqlx_test_databases where created_at < sysutcdatetime()")
+            .fetch_all(&mut *conn)
+            .await?;
+
+    if delete_db_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let mut deleted_db_ids = Vec::with_capacity(delete_db_ids.len());
+
+    for db_id in delete_db_ids {
+        let db_name = db_name(db_id);
+
+        match conn.execute(&*drop_database(&db_name)).await {
+            Ok(_deleted) => {
+                deleted_db_ids.push(db_id);
+            }
+            // Assume a database error just means the DB is still in use.
+            Err(Error::Database(dbe)) => {
+                eprintln!("could not clean test database {:?}: {}", db_id, dbe)
+            }
+            // Bubble up other errors
+            Err(e) => return Err(e),
+        }
+    }
+
+    if deleted_db_ids.is_empty() {
+        return Ok(0);
+    }
+
+    // the IDs are integers that we generated, so they are safe to inline
+    let mut command = String::from("delete from _sqlx_test_databases where db_id in (");
+
+    for (i, db_id) in deleted_db_ids.iter().enumerate() {
+        if i > 0 {
+            command.push(',');
+        }
+
+        write!(command, "{}", db_id).ok();
+    }
+
+    command.push(')');
+
+    conn.execute(&*command).await?;
+
+    Ok(deleted_db_ids.len())
+}
+
+fn drop_database(db_name: &str) -> String {
+    format!("drop database if exists {};", quote_identifier(db_name))
+}
+
+fn db_name(id: i64) -> String {
+    format!("_sqlx_test_database_{}", id)
+}
+
+fn db_id(name: &str) -> i64 {
+    name.trim_start_matches("_sqlx_test_database_")
+        .parse()
+        .unwrap_or_else(|_1| panic!("failed to parse ID from database name {:?}", name))
+}
+
+#[test]
+fn test_db_name_id() {
+    assert_eq!(db_name(12345), "_sqlx_test_database_12345");
+    assert_eq!(db_id("_sqlx_test_database_12345"), 12345);
+}
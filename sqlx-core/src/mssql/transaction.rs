@@ -7,7 +7,7 @@ use crate::executor::Executor;
 use crate::mssql::protocol::packet::PacketType;
 use crate::mssql::protocol::sql_batch::SqlBatch;
 use crate::mssql::{Mssql, MssqlConnection};
-use crate::transaction::TransactionManager;
+use crate::transaction::{check_savepoint_options, TransactionManager, TransactionOptions};
 use crate::mssql::protocol::packet::Status;
 use crate::mssql::protocol::packet::PacketHeader;
 use crate::io::Encode;
@@ -19,22 +19,71 @@ impl TransactionManager for MssqlTransactionManager {
     type Database = Mssql;
 
     fn begin(conn: &mut MssqlConnection) -> BoxFuture<'_, Result<(), Error>> {
+        Self::begin_with(conn, TransactionOptions::default())
+    }
+
+    fn begin_with(
+        conn: &mut MssqlConnection,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
             let depth = conn.stream.transaction_depth;
 
-            let query = if depth == 0 {
-                Cow::Borrowed("BEGIN TRAN ")
-            } else {
-                Cow::Owned(format!("SAVE TRAN _sqlx_savepoint_{}", depth))
+            check_savepoint_options(depth, &options)?;
+
+            // read-only and deferrable transactions are not supported by MSSQL,
+            // so only the isolation level is applied
+            let query = match (depth, options.isolation_level) {
+                (0, Some(level)) => Cow::Owned(format!(
+                    "SET TRANSACTION ISOLATION LEVEL {}; BEGIN TRAN ",
+                    level
+                )),
+                (0, None) => Cow::Borrowed("BEGIN TRAN "),
+                _ => Cow::Owned(format!("SAVE TRAN _sqlx_savepoint_{}", depth)),
             };
 
             conn.execute(&*query).await?;
             conn.stream.transaction_depth = depth + 1;
 
+            if depth == 0 && options.isolation_level.is_some() {
+                // the isolation level outlives the transaction
+                conn.stream.isolation_level_changed = true;
+            }
+
+            Ok(())
+        })
+    }
+
+    fn savepoint<'c>(
+        conn: &'c mut MssqlConnection,
+        name: &'c str,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        Box::pin(async move {
+            conn.execute(&*format!("SAVE TRAN {}", name)).await?;
+
             Ok(())
         })
     }
 
+    fn rollback_to_savepoint<'c>(
+        conn: &'c mut MssqlConnection,
+        name: &'c str,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        Box::pin(async move {
+            conn.execute(&*format!("ROLLBACK TRAN {}", name)).await?;
+
+            Ok(())
+        })
+    }
+
+    fn release_savepoint<'c>(
+        _conn: &'c mut MssqlConnection,
+        _name: &'c str,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        // savepoints are not released in MSSQL
+        Box::pin(async move { Ok(()) })
+    }
+
     fn commit(conn: &mut MssqlConnection) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
             let depth = conn.stream.transaction_depth;
@@ -42,7 +91,8 @@ impl TransactionManager for MssqlTransactionManager {
             if depth > 0 {
                 if depth == 1 {
                     // savepoints are not released in MSSQL
-                    conn.execute("COMMIT TRAN").await?;
+                    let query = end_transaction_sql(conn, "COMMIT TRAN");
+                    conn.execute(&*query).await?;
                 }
 
                 conn.stream.transaction_depth = depth - 1;
@@ -57,13 +107,10 @@ impl TransactionManager for MssqlTransactionManager {
             let depth = conn.stream.transaction_depth;
 
             if depth > 0 {
-                let query = if depth == 1 {
-                    Cow::Borrowed("ROLLBACK TRAN")
-                } else {
-                    Cow::Owned(format!("ROLLBACK TRAN _sqlx_savepoint_{}", depth - 1))
-                };
+                if let Some(query) = rollback_sql(conn, depth) {
+                    conn.execute(&*query).await?;
+                }
 
-                conn.execute(&*query).await?;
                 conn.stream.transaction_depth = depth - 1;
             }
 
@@ -75,33 +122,62 @@ impl TransactionManager for MssqlTransactionManager {
         let depth = conn.stream.transaction_depth;
 
         if depth > 0 {
-            let query = if depth == 1 {
-                Cow::Borrowed("ROLLBACK TRAN")
-            } else {
-                Cow::Owned(format!("ROLLBACK TRAN _sqlx_savepoint_{}", depth - 1))
-            };
-
-            conn.stream.pending_done_count += 1;
-            let header = PacketHeader {
-                r#type: PacketType::SqlBatch,
-                status: Status::END_OF_MESSAGE,
-                length: 0,
-                server_process_id: 0,
-                packet_id: 1,
-            };
-            let batch = SqlBatch {
-                transaction_descriptor: conn.stream.transaction_descriptor,
-                sql: &*query,
-            };
-            let mut batch_buf = Vec::<u8>::new();
-            batch.encode_with(&mut batch_buf, ());
+            if let Some(query) = rollback_sql(conn, depth) {
+                conn.stream.pending_done_count += 1;
+                let header = PacketHeader {
+                    r#type: PacketType::SqlBatch,
+                    status: Status::END_OF_MESSAGE,
+                    length: 0,
+                    server_process_id: 0,
+                    packet_id: 1,
+                };
+                let batch = SqlBatch {
+                    transaction_descriptor: conn.stream.transaction_descriptor,
+                    sql: &*query,
+                };
+                let mut batch_buf = Vec::<u8>::new();
+                batch.encode_with(&mut batch_buf, ());
 
-            let _ = conn.stream.write_packet1(
-                header,
-                &batch_buf,
-            );
+                let _ = conn.stream.write_packet1(header, &batch_buf);
+            }
 
             conn.stream.transaction_depth = depth - 1;
         }
     }
 }
+
+// the server rolls back the whole transaction by itself when it is chosen as a deadlock
+// victim, after which there is nothing left to roll back
+fn rollback_sql(conn: &mut MssqlConnection, depth: usize) -> Option<Cow<'static, str>> {
+    if conn.stream.transaction_descriptor == 0 {
+        if depth == 1 && conn.stream.isolation_level_changed {
+            conn.stream.isolation_level_changed = false;
+
+            return Some(Cow::Borrowed(
+                "SET TRANSACTION ISOLATION LEVEL READ COMMITTED",
+            ));
+        }
+
+        return None;
+    }
+
+    Some(if depth == 1 {
+        end_transaction_sql(conn, "ROLLBACK TRAN")
+    } else {
+        Cow::Owned(format!("ROLLBACK TRAN _sqlx_savepoint_{}", depth - 1))
+    })
+}
+
+// restores the default isolation level of the session after a transaction that changed it
+fn end_transaction_sql(conn: &mut MssqlConnection, query: &'static str) -> Cow<'static, str> {
+    if conn.stream.isolation_level_changed {
+        conn.stream.isolation_level_changed = false;
+
+        Cow::Owned(format!(
+            "{}; SET TRANSACTION ISOLATION LEVEL READ COMMITTED",
+            query
+        ))
+    } else {
+        Cow::Borrowed(query)
+    }
+}
@@ -37,6 +37,7 @@ impl Encode<'_, Mssql> for Binary {
             scale: 0,
             precision: 0,
             collation: None,
+            type_name: None,
         }))
     }
 
@@ -31,6 +31,7 @@ impl Encode<'_, Mssql> for BitVec {
             scale: 0,
             precision: 0,
             collation: None,
+            type_name: None,
         }))
     }
 
@@ -54,7 +54,7 @@ impl Encode<'_, Mssql> for Vec<u8> {
 
 impl Decode<'_, Mssql> for Vec<u8> {
     fn decode(value: MssqlValueRef<'_>) -> Result<Self, BoxDynError> {
-        <&[u8] as Decode<Mssql>>::decode(value).map(ToOwned::to_owned)
+        Ok(value.to_bytes()?.into_owned())
     }
 }
 
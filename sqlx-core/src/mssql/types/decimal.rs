@@ -18,6 +18,7 @@ impl Type<Mssql> for BigDecimal {
             precision: 38,
             scale: 8,
             collation: None,
+            type_name: None,
         })
     }
 
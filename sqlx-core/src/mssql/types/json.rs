@@ -0,0 +1,55 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::mssql::{Mssql, MssqlTypeInfo, MssqlValueRef};
+use crate::types::{Json, Type};
+
+impl<T> Type<Mssql> for Json<T> {
+    fn type_info() -> MssqlTypeInfo {
+        // SQL Server has no JSON type, documents are stored as `nvarchar(max)`
+        let mut ty = <str as Type<Mssql>>::type_info();
+        ty.0.size = 0xFFFF;
+        ty
+    }
+
+    fn compatible(ty: &MssqlTypeInfo) -> bool {
+        <str as Type<Mssql>>::compatible(ty)
+    }
+}
+
+impl<T> Encode<'_, Mssql> for Json<T>
+where
+    T: Serialize,
+{
+    fn produces(&self) -> Option<MssqlTypeInfo> {
+        // the length of the document is not known until it is serialized, so it is always
+        // sent as `nvarchar(max)`
+        let mut ty = <&str as Encode<Mssql>>::produces(&"")?;
+        ty.0.size = 0xFFFF;
+
+        Some(ty)
+    }
+
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        let json_string_value =
+            serde_json::to_string(&self.0).expect("serde_json failed to convert to string");
+
+        <&str as Encode<Mssql>>::encode(json_string_value.as_str(), buf)
+    }
+}
+
+impl<'r, T> Decode<'r, Mssql> for Json<T>
+where
+    T: 'r + DeserializeOwned,
+{
+    fn decode(value: MssqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        let string_value = <String as Decode<Mssql>>::decode(value)?;
+
+        serde_json::from_str(&string_value)
+            .map(Json)
+            .map_err(Into::into)
+    }
+}
@@ -6,16 +6,33 @@ mod bool;
 mod float;
 mod int;
 mod str;
+mod tvp;
 mod uint;
 mod decimal;
+mod plp;
+mod varchar;
+mod xml;
 // mod binary;
 mod bit_vec;
 mod bytes;
 pub use bytes::{ToSqlValue, VecBin, SliceBin};
+pub use plp::MssqlPlpReader;
+pub use tvp::{TableValuedParameter, TableValuedRow};
+pub use varchar::MssqlVarChar;
+pub use xml::MssqlXml;
 
 #[cfg(feature = "chrono")]
 mod chrono;
 
+#[cfg(feature = "json")]
+mod json;
+
+#[cfg(feature = "time")]
+mod time;
+
+#[cfg(feature = "uuid")]
+mod uuid;
+
 
 impl<'q, T: 'q + Encode<'q, Mssql>> Encode<'q, Mssql> for Option<T> {
     fn encode(self, buf: &mut Vec<u8>) -> IsNull {
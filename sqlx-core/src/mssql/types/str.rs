@@ -23,6 +23,7 @@ impl Type<Mssql> for str {
                 | DataType::Char
                 | DataType::NText
                 | DataType::Text
+                | DataType::Xml
         )
     }
 }
@@ -53,6 +54,7 @@ impl Encode<'_, Mssql> for &'_ str {
                 sort: 52,
                 version: 0,
             }),
+            type_name: None,
         }))
     }
 
@@ -79,7 +81,7 @@ impl Decode<'_, Mssql> for String {
             .type_info
             .0
             .encoding()?
-            .decode_without_bom_handling(value.as_bytes()?)
+            .decode_without_bom_handling(&value.to_bytes()?)
             .0
             .into_owned())
     }
@@ -118,7 +120,7 @@ impl<'r> Decode<'r, Mssql> for Cow<'r, str> {
                 .type_info
                 .0
                 .encoding()?
-                .decode_without_bom_handling(value.as_bytes()?)
+                .decode_without_bom_handling(&value.to_bytes()?)
                 .0
                 .into_owned(),
         ))
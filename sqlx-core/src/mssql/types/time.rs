@@ -0,0 +1,300 @@
+use std::cmp;
+
+use byteorder::{ByteOrder, LittleEndian};
+use time::macros::date;
+use time::{Date, Duration, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::mssql::protocol::type_info::{DataType, TypeInfo};
+use crate::mssql::{Mssql, MssqlTypeInfo, MssqlValueRef};
+use crate::types::Type;
+
+// `date`, `datetime2` and `datetimeoffset` count days from 0001-01-01
+const DATE_EPOCH: Date = date!(0001 - 01 - 01);
+
+// `datetime` and `smalldatetime` count days from 1900-01-01
+const DATETIME_EPOCH: Date = date!(1900 - 01 - 01);
+
+// we always send values with the highest precision (100ns)
+const SCALE: u8 = 7;
+
+impl Type<Mssql> for Date {
+    fn type_info() -> MssqlTypeInfo {
+        MssqlTypeInfo(TypeInfo::new(DataType::DateN, 3))
+    }
+}
+
+impl Type<Mssql> for Time {
+    fn type_info() -> MssqlTypeInfo {
+        let mut ty = TypeInfo::new(DataType::TimeN, time_len(SCALE) as u32);
+        ty.scale = SCALE;
+        MssqlTypeInfo(ty)
+    }
+
+    fn compatible(ty: &MssqlTypeInfo) -> bool {
+        // `time(n)` of any scale
+        ty.0.ty == DataType::TimeN
+    }
+}
+
+impl Type<Mssql> for PrimitiveDateTime {
+    fn type_info() -> MssqlTypeInfo {
+        let mut ty = TypeInfo::new(DataType::DateTime2N, time_len(SCALE) as u32 + 3);
+        ty.scale = SCALE;
+        MssqlTypeInfo(ty)
+    }
+
+    fn compatible(ty: &MssqlTypeInfo) -> bool {
+        matches!(
+            ty.0.ty,
+            DataType::DateTime2N
+                | DataType::DateTimeN
+                | DataType::DateTime
+                | DataType::SmallDateTime
+        )
+    }
+}
+
+impl Type<Mssql> for OffsetDateTime {
+    fn type_info() -> MssqlTypeInfo {
+        let mut ty = TypeInfo::new(DataType::DateTimeOffsetN, time_len(SCALE) as u32 + 5);
+        ty.scale = SCALE;
+        MssqlTypeInfo(ty)
+    }
+
+    fn compatible(ty: &MssqlTypeInfo) -> bool {
+        matches!(ty.0.ty, DataType::DateTimeOffsetN | DataType::DateTime2N)
+    }
+}
+
+impl Encode<'_, Mssql> for Date {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        encode_date(*self, buf);
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, Mssql> for Date {
+    fn decode(value: MssqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        decode_date(value.as_bytes()?)
+    }
+}
+
+impl Encode<'_, Mssql> for Time {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        encode_time(*self, SCALE, buf);
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, Mssql> for Time {
+    fn decode(value: MssqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        decode_time(value.as_bytes()?, value.type_info.0.scale)
+    }
+}
+
+impl Encode<'_, Mssql> for PrimitiveDateTime {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        encode_time(self.time(), SCALE, buf);
+        encode_date(self.date(), buf);
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, Mssql> for PrimitiveDateTime {
+    fn decode(value: MssqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        let buf = value.as_bytes()?;
+
+        match value.type_info.0.ty {
+            DataType::DateTime2N => decode_datetime2(buf, value.type_info.0.scale),
+
+            DataType::DateTimeN | DataType::DateTime | DataType::SmallDateTime => {
+                decode_datetime(buf)
+            }
+
+            ty => Err(format!("unexpected data type {:?} for PrimitiveDateTime", ty).into()),
+        }
+    }
+}
+
+impl Encode<'_, Mssql> for OffsetDateTime {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        // the date and time are sent in UTC, followed by the offset in minutes
+        let utc = self.to_offset(UtcOffset::UTC);
+
+        encode_time(utc.time(), SCALE, buf);
+        encode_date(utc.date(), buf);
+        buf.extend(&self.offset().whole_minutes().to_le_bytes());
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, Mssql> for OffsetDateTime {
+    fn decode(value: MssqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        let buf = value.as_bytes()?;
+        let scale = value.type_info.0.scale;
+        let utc = decode_datetime2(buf, scale)?.assume_utc();
+
+        if value.type_info.0.ty != DataType::DateTimeOffsetN {
+            // datetime2 has no offset, assume UTC
+            return Ok(utc);
+        }
+
+        let offset = buf
+            .get(time_len(scale) + 3..time_len(scale) + 5)
+            .ok_or("expected 2 bytes for the offset of a datetimeoffset")?;
+
+        let offset = UtcOffset::from_whole_seconds(LittleEndian::read_i16(offset) as i32 * 60)?;
+
+        Ok(utc.to_offset(offset))
+    }
+}
+
+// The number of bytes used by the time part of time(n), datetime2(n) and datetimeoffset(n)
+fn time_len(scale: u8) -> usize {
+    match scale {
+        0..=2 => 3,
+        3..=4 => 4,
+        _ => 5,
+    }
+}
+
+// The time is the number of 10^-n second units since midnight, where n is the scale
+fn encode_time(time: Time, scale: u8, buf: &mut Vec<u8>) {
+    let nanos = (time - Time::MIDNIGHT).whole_nanoseconds() as u64;
+    let ticks = nanos / 10_u64.pow(9 - cmp::min(scale, 7) as u32);
+
+    buf.extend(&ticks.to_le_bytes()[..time_len(scale)]);
+}
+
+fn decode_time(buf: &[u8], scale: u8) -> Result<Time, BoxDynError> {
+    let len = time_len(scale);
+    let ticks = buf
+        .get(..len)
+        .ok_or_else(|| format!("expected {} bytes for a time({})", len, scale))?;
+
+    let ticks = LittleEndian::read_uint(ticks, len);
+    let nanos = ticks * 10_u64.pow(9 - cmp::min(scale, 7) as u32);
+
+    Ok(Time::MIDNIGHT + Duration::nanoseconds(nanos as i64))
+}
+
+// The date is the number of days since 0001-01-01, in 3 bytes
+fn encode_date(date: Date, buf: &mut Vec<u8>) {
+    let days = (date - DATE_EPOCH).whole_days() as u32;
+
+    buf.extend(&days.to_le_bytes()[..3]);
+}
+
+fn decode_date(buf: &[u8]) -> Result<Date, BoxDynError> {
+    let days = buf.get(..3).ok_or("expected 3 bytes for a date")?;
+    let days = LittleEndian::read_u24(days);
+
+    Ok(DATE_EPOCH + Duration::days(days.into()))
+}
+
+// datetime2(n) is a time(n) followed by a date
+fn decode_datetime2(buf: &[u8], scale: u8) -> Result<PrimitiveDateTime, BoxDynError> {
+    let time = decode_time(buf, scale)?;
+    let date = decode_date(buf.get(time_len(scale)..).unwrap_or_default())?;
+
+    Ok(PrimitiveDateTime::new(date, time))
+}
+
+// datetime is the number of days since 1900-01-01 followed by the number of 1/300 seconds
+// since midnight, smalldatetime is the number of days followed by the number of minutes
+fn decode_datetime(buf: &[u8]) -> Result<PrimitiveDateTime, BoxDynError> {
+    let (days, time) = match buf.len() {
+        8 => {
+            let days = LittleEndian::read_i32(&buf[0..4]);
+            let ticks = LittleEndian::read_u32(&buf[4..8]) as i64;
+
+            (
+                days as i64,
+                Duration::nanoseconds(ticks * 1_000_000_000 / 300),
+            )
+        }
+
+        4 => {
+            let days = LittleEndian::read_u16(&buf[0..2]);
+            let minutes = LittleEndian::read_u16(&buf[2..4]);
+
+            (days as i64, Duration::minutes(minutes.into()))
+        }
+
+        len => return Err(format!("unexpected length {} for a datetime", len).into()),
+    };
+
+    Ok((DATETIME_EPOCH + Duration::days(days)).midnight() + time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::{datetime, time};
+
+    #[test]
+    fn test_encode_time() {
+        let mut buf = Vec::new();
+        encode_time(time!(12:34:56.1234567), 7, &mut buf);
+
+        assert_eq!(buf.len(), 5);
+        assert_eq!(
+            LittleEndian::read_uint(&buf, 5),
+            (12 * 3600 + 34 * 60 + 56) * 10_000_000 + 1_234_567
+        );
+        assert_eq!(decode_time(&buf, 7).unwrap(), time!(12:34:56.1234567));
+
+        let mut buf = Vec::new();
+        encode_time(time!(12:34:56.1234567), 3, &mut buf);
+
+        assert_eq!(buf.len(), 4);
+        assert_eq!(decode_time(&buf, 3).unwrap(), time!(12:34:56.123));
+
+        let mut buf = Vec::new();
+        encode_time(time!(23:59:59.99), 0, &mut buf);
+
+        assert_eq!(buf.len(), 3);
+        assert_eq!(decode_time(&buf, 0).unwrap(), time!(23:59:59));
+    }
+
+    #[test]
+    fn test_encode_date() {
+        let mut buf = Vec::new();
+        encode_date(date!(0001 - 01 - 01), &mut buf);
+        assert_eq!(buf, [0, 0, 0]);
+
+        let mut buf = Vec::new();
+        encode_date(date!(2022 - 10 - 18), &mut buf);
+        assert_eq!(decode_date(&buf).unwrap(), date!(2022 - 10 - 18));
+    }
+
+    #[test]
+    fn test_decode_datetime() {
+        // 1900-01-02 00:00:01
+        let mut buf = Vec::new();
+        buf.extend(&1_i32.to_le_bytes());
+        buf.extend(&300_u32.to_le_bytes());
+
+        assert_eq!(
+            decode_datetime(&buf).unwrap(),
+            datetime!(1900 - 01 - 02 00:00:01)
+        );
+
+        // 1900-01-02 00:02
+        let mut buf = Vec::new();
+        buf.extend(&1_u16.to_le_bytes());
+        buf.extend(&2_u16.to_le_bytes());
+
+        assert_eq!(
+            decode_datetime(&buf).unwrap(),
+            datetime!(1900 - 01 - 02 00:02)
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use crate::encode::{Encode, IsNull};
+use crate::mssql::protocol::col_meta_data::Flags;
+use crate::mssql::protocol::type_info::{DataType, TypeInfo};
+use crate::mssql::{Mssql, MssqlTypeInfo};
+use crate::types::Type;
+
+/// A table-valued parameter (TVP), bound to a user-defined table type on the server.
+///
+/// The columns must be declared in the same order as in the table type, and every row must
+/// have a value for each column.
+///
+/// ```rust
+/// # use sqlx_core::mssql::types::TableValuedParameter;
+/// // CREATE TYPE dbo.UserList AS TABLE (id INT, name NVARCHAR(100));
+/// let mut users = TableValuedParameter::new("dbo.UserList")
+///     .column::<i32>()
+///     .column::<String>();
+///
+/// users.add_row().push(1_i32).push("alice");
+/// users.add_row().push(2_i32).push(Option::<&str>::None);
+///
+/// let query = sqlx_core::query::query("SELECT id, name FROM @p1").bind(users);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TableValuedParameter {
+    type_name: String,
+    columns: Vec<TypeInfo>,
+
+    // encoded TVP_ROW tokens
+    rows: Vec<u8>,
+
+    // number of values pushed onto the last row
+    row_len: usize,
+}
+
+/// A row of a [`TableValuedParameter`], returned by [`TableValuedParameter::add_row`].
+pub struct TableValuedRow<'a> {
+    tvp: &'a mut TableValuedParameter,
+}
+
+const TVP_ROW_TOKEN: u8 = 0x01;
+const TVP_END_TOKEN: u8 = 0x00;
+
+impl TableValuedParameter {
+    /// Create an empty table-valued parameter of the named table type, e.g. `dbo.UserList`.
+    pub fn new(type_name: &str) -> Self {
+        Self {
+            type_name: type_name.to_owned(),
+            columns: Vec::new(),
+            rows: Vec::new(),
+            row_len: 0,
+        }
+    }
+
+    /// Declare the next column of the table type, taking its SQL type from `T`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a row has already been added.
+    pub fn column<T: Type<Mssql> + ?Sized>(mut self) -> Self {
+        assert!(
+            self.rows.is_empty(),
+            "columns must be declared before any row is added"
+        );
+
+        self.columns.push(T::type_info().0.into_column_type());
+        self
+    }
+
+    /// Start a new row; push a value for each column onto the returned [`TableValuedRow`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the previous row is missing values.
+    pub fn add_row(&mut self) -> TableValuedRow<'_> {
+        self.assert_row_complete();
+
+        self.rows.push(TVP_ROW_TOKEN);
+        self.row_len = 0;
+
+        TableValuedRow { tvp: self }
+    }
+
+    fn assert_row_complete(&self) {
+        assert!(
+            self.rows.is_empty() || self.row_len == self.columns.len(),
+            "row of table-valued parameter `{}` has {} values but the table type has {} columns",
+            self.type_name,
+            self.row_len,
+            self.columns.len()
+        );
+    }
+}
+
+impl TableValuedRow<'_> {
+    /// Push the value of the next column onto this row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row already has a value for every column, or if the type of the value
+    /// is not compatible with the type of the column.
+    pub fn push<'q, T: 'q + Encode<'q, Mssql> + Type<Mssql>>(self, value: T) -> Self {
+        let tvp = &mut *self.tvp;
+
+        let column = tvp.columns.get(tvp.row_len).unwrap_or_else(|| {
+            panic!(
+                "table-valued parameter `{}` only has {} columns",
+                tvp.type_name,
+                tvp.columns.len()
+            )
+        });
+
+        let column = MssqlTypeInfo(column.clone());
+
+        assert!(
+            T::compatible(&column),
+            "value of type `{}` is not compatible with column {} of type `{}`",
+            std::any::type_name::<T>(),
+            tvp.row_len,
+            column.0.name()
+        );
+
+        // values are always encoded using the TYPE_INFO of the column
+        column.0.put_value(&mut tvp.rows, value);
+        tvp.row_len += 1;
+
+        self
+    }
+}
+
+impl Type<Mssql> for TableValuedParameter {
+    fn type_info() -> MssqlTypeInfo {
+        MssqlTypeInfo(TypeInfo::new(DataType::Tvp, 0))
+    }
+}
+
+impl Encode<'_, Mssql> for TableValuedParameter {
+    fn produces(&self) -> Option<MssqlTypeInfo> {
+        let mut ty = TypeInfo::new(DataType::Tvp, 0);
+        ty.type_name = Some(self.type_name.clone());
+
+        Some(MssqlTypeInfo(ty))
+    }
+
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        self.assert_row_complete();
+
+        // TVP_COLMETADATA
+        buf.extend(&(self.columns.len() as u16).to_le_bytes());
+
+        for column in &self.columns {
+            buf.extend(&0_u32.to_le_bytes()); // [UserType]
+            buf.extend(&Flags::NULLABLE.bits().to_le_bytes()); // [Flags]
+            column.put(buf); // [TYPE_INFO]
+            buf.push(0); // [ColName], always empty
+        }
+
+        buf.push(TVP_END_TOKEN);
+
+        // TVP_ROW*
+        buf.extend_from_slice(&self.rows);
+        buf.push(TVP_END_TOKEN);
+
+        IsNull::No
+    }
+
+    fn size_hint(&self) -> usize {
+        self.rows.len() + self.columns.len() * 16 + 4
+    }
+}
+
+#[test]
+fn test_encode_tvp() {
+    let mut tvp = TableValuedParameter::new("dbo.IdList").column::<i32>();
+
+    tvp.add_row().push(1_i32);
+    tvp.add_row().push(Option::<i32>::None);
+
+    let mut buf = Vec::new();
+    let _ = tvp.encode_by_ref(&mut buf);
+
+    #[rustfmt::skip]
+    assert_eq!(buf, [
+        // TVP_COLMETADATA
+        1, 0, 0, 0, 0, 0, 1, 0, 0x26, 4, 0,
+        TVP_END_TOKEN,
+        // TVP_ROW
+        TVP_ROW_TOKEN, 4, 1, 0, 0, 0,
+        TVP_ROW_TOKEN, 0,
+        TVP_END_TOKEN,
+    ]);
+
+    let mut type_info = Vec::new();
+    tvp.produces().unwrap().0.put(&mut type_info);
+
+    assert_eq!(
+        type_info,
+        [
+            0xf3, 0, 3, b'd', 0, b'b', 0, b'o', 0, 6, b'I', 0, b'd', 0, b'L', 0, b'i', 0, b's', 0,
+            b't', 0
+        ]
+    );
+}
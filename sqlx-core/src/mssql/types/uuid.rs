@@ -0,0 +1,53 @@
+use uuid::Uuid;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::mssql::protocol::type_info::{DataType, TypeInfo};
+use crate::mssql::{Mssql, MssqlTypeInfo, MssqlValueRef};
+use crate::types::Type;
+
+impl Type<Mssql> for Uuid {
+    fn type_info() -> MssqlTypeInfo {
+        MssqlTypeInfo(TypeInfo::new(DataType::Guid, 16))
+    }
+
+    fn compatible(ty: &MssqlTypeInfo) -> bool {
+        matches!(ty.0.ty, DataType::Guid)
+    }
+}
+
+// NOTE: SQL Server stores a UNIQUEIDENTIFIER in a mixed-endian byte order; the first three
+//       groups are little-endian while the last two are big-endian, i.e. as `to_bytes_le`
+
+impl Encode<'_, Mssql> for Uuid {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        buf.extend_from_slice(&self.to_bytes_le());
+
+        IsNull::No
+    }
+}
+
+impl Decode<'_, Mssql> for Uuid {
+    fn decode(value: MssqlValueRef<'_>) -> Result<Self, BoxDynError> {
+        let bytes: [u8; 16] = value.as_bytes()?.try_into()?;
+
+        Ok(Uuid::from_bytes_le(bytes))
+    }
+}
+
+#[test]
+fn test_encode_uuid() {
+    let uuid = Uuid::parse_str("6f9619ff-8b86-d011-b42d-00c04fc964ff").unwrap();
+
+    let mut buf = Vec::new();
+    let _ = uuid.encode_by_ref(&mut buf);
+
+    assert_eq!(
+        buf,
+        [
+            0xff, 0x19, 0x96, 0x6f, 0x86, 0x8b, 0x11, 0xd0, 0xb4, 0x2d, 0x00, 0xc0, 0x4f, 0xc9,
+            0x64, 0xff
+        ]
+    );
+}
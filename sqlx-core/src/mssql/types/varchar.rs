@@ -0,0 +1,84 @@
+use std::cmp;
+use std::ops::Deref;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::mssql::protocol::type_info::{DataType, TypeInfo};
+use crate::mssql::{Mssql, MssqlTypeInfo, MssqlValueRef};
+use crate::types::Type;
+
+/// A string sent as a non-Unicode `varchar`, in the code page of the database's collation.
+///
+/// Strings are bound as `nvarchar` by default. Comparing an `nvarchar` parameter with a
+/// `varchar` column converts the column instead, which prevents the server from seeking an
+/// index on it; `MssqlVarChar` avoids that for legacy tables.
+///
+/// The value is encoded with the collation of the database when it is sent. In a bulk load,
+/// it is encoded with the collation of the column. Characters that cannot be represented in
+/// the code page are replaced with `?`, as the server does when converting an `nvarchar`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct MssqlVarChar(pub String);
+
+impl Deref for MssqlVarChar {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<String> for MssqlVarChar {
+    fn from(s: String) -> Self {
+        Self(s)
+    }
+}
+
+impl From<&'_ str> for MssqlVarChar {
+    fn from(s: &str) -> Self {
+        Self(s.to_owned())
+    }
+}
+
+impl From<MssqlVarChar> for String {
+    fn from(s: MssqlVarChar) -> Self {
+        s.0
+    }
+}
+
+impl Type<Mssql> for MssqlVarChar {
+    fn type_info() -> MssqlTypeInfo {
+        MssqlTypeInfo(TypeInfo::new(DataType::BigVarChar, 0))
+    }
+
+    fn compatible(ty: &MssqlTypeInfo) -> bool {
+        <str as Type<Mssql>>::compatible(ty)
+    }
+}
+
+impl Encode<'_, Mssql> for MssqlVarChar {
+    fn produces(&self) -> Option<MssqlTypeInfo> {
+        // the collation is only known once the value is sent
+        //
+        // `varchar(n)` is limited to 8000 bytes, longer values are sent as `varchar(max)`
+        let size = match self.0.len() {
+            len if len > 8000 => 0xFFFF,
+            len => cmp::max(len as u32, 1),
+        };
+
+        Some(MssqlTypeInfo(TypeInfo::new(DataType::BigVarChar, size)))
+    }
+
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        // the value is written as UTF-8 and converted to the code page of the collation
+        buf.extend(self.0.as_bytes());
+
+        IsNull::No
+    }
+}
+
+impl Decode<'_, Mssql> for MssqlVarChar {
+    fn decode(value: MssqlValueRef<'_>) -> Result<Self, BoxDynError> {
+        <String as Decode<Mssql>>::decode(value).map(Self)
+    }
+}
@@ -0,0 +1,67 @@
+use std::ops::Deref;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::mssql::io::MssqlBufMutExt;
+use crate::mssql::protocol::type_info::{DataType, TypeInfo};
+use crate::mssql::{Mssql, MssqlTypeInfo, MssqlValueRef};
+use crate::types::Type;
+
+/// A document of the SQL Server `xml` type.
+///
+/// The server checks that the document is well formed when it is sent, but the driver does not
+/// parse it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct MssqlXml(pub String);
+
+impl Deref for MssqlXml {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<String> for MssqlXml {
+    fn from(xml: String) -> Self {
+        Self(xml)
+    }
+}
+
+impl From<MssqlXml> for String {
+    fn from(xml: MssqlXml) -> Self {
+        xml.0
+    }
+}
+
+impl Type<Mssql> for MssqlXml {
+    fn type_info() -> MssqlTypeInfo {
+        MssqlTypeInfo(TypeInfo::new(DataType::Xml, 0xFFFF))
+    }
+
+    fn compatible(ty: &MssqlTypeInfo) -> bool {
+        <str as Type<Mssql>>::compatible(ty)
+    }
+}
+
+impl Encode<'_, Mssql> for MssqlXml {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        buf.put_utf16_str(&self.0);
+
+        IsNull::No
+    }
+}
+
+impl Decode<'_, Mssql> for MssqlXml {
+    fn decode(value: MssqlValueRef<'_>) -> Result<Self, BoxDynError> {
+        let mut xml = <String as Decode<Mssql>>::decode(value)?;
+
+        // the document may start with a byte order mark
+        if xml.starts_with('\u{feff}') {
+            xml.remove(0);
+        }
+
+        Ok(Self(xml))
+    }
+}
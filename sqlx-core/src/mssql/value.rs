@@ -1,4 +1,5 @@
 use crate::error::{BoxDynError, UnexpectedNullError};
+use crate::mssql::types::MssqlPlpReader;
 use crate::mssql::{Mssql, MssqlTypeInfo};
 use crate::value::{Value, ValueRef};
 use bytes::Bytes;
@@ -13,9 +14,38 @@ pub struct MssqlValueRef<'r> {
 
 impl<'r> MssqlValueRef<'r> {
     pub(crate) fn as_bytes(&self) -> Result<&'r [u8], BoxDynError> {
-        match &self.data {
-            Some(v) => Ok(v),
-            None => Err(UnexpectedNullError.into()),
+        match self.to_bytes()? {
+            Cow::Borrowed(v) => Ok(v),
+            Cow::Owned(_) => Err(
+                "value was sent in more than one chunk and cannot be borrowed; \
+                 decode it as an owned type or with `MssqlPlpReader` instead"
+                    .into(),
+            ),
+        }
+    }
+
+    // the bytes of the value, joining the chunks of a PLP value if there is more than one
+    pub(crate) fn to_bytes(&self) -> Result<Cow<'r, [u8]>, BoxDynError> {
+        let data: &'r [u8] = self.data.ok_or(UnexpectedNullError)?;
+
+        if !self.type_info.0.is_plp() {
+            return Ok(Cow::Borrowed(data));
+        }
+
+        let mut chunks = MssqlPlpReader::new(data);
+        let first = chunks.next().unwrap_or_default();
+
+        match chunks.next() {
+            None => Ok(Cow::Borrowed(first)),
+
+            Some(second) => {
+                let mut joined = Vec::with_capacity(data.len());
+                joined.extend_from_slice(first);
+                joined.extend_from_slice(second);
+                chunks.for_each(|chunk| joined.extend_from_slice(chunk));
+
+                Ok(Cow::Owned(joined))
+            }
         }
     }
 }
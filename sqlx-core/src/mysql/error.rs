@@ -1,7 +1,7 @@
 use std::error::Error;
 use std::fmt::{self, Debug, Display, Formatter};
 
-use crate::error::DatabaseError;
+use crate::error::{DatabaseError, ErrorKind};
 use crate::mysql::protocol::response::ErrPacket;
 use smallvec::alloc::borrow::Cow;
 
@@ -62,6 +62,36 @@ impl DatabaseError for MySqlDatabaseError {
         self.code().map(Cow::Borrowed)
     }
 
+    fn is_retryable(&self) -> bool {
+        // https://dev.mysql.com/doc/refman/8.0/en/innodb-error-handling.html
+        matches!(
+            self.number(),
+            // ER_LOCK_DEADLOCK, the transaction was rolled back
+            1213
+            // ER_LOCK_WAIT_TIMEOUT, the statement was rolled back
+            | 1205
+        )
+    }
+
+    fn kind(&self) -> ErrorKind {
+        // https://dev.mysql.com/doc/mysql-errors/8.0/en/server-error-reference.html
+        match self.number() {
+            // ER_DUP_ENTRY, ER_DUP_ENTRY_WITH_KEY_NAME
+            1062 | 1586 => ErrorKind::UniqueViolation,
+            // ER_NO_REFERENCED_ROW(_2), ER_ROW_IS_REFERENCED(_2)
+            1216 | 1217 | 1451 | 1452 => ErrorKind::ForeignKeyViolation,
+            // ER_BAD_NULL_ERROR, ER_NO_DEFAULT_FOR_FIELD
+            1048 | 1364 => ErrorKind::NotNullViolation,
+            // ER_CHECK_CONSTRAINT_VIOLATED
+            3819 => ErrorKind::CheckViolation,
+            // ER_LOCK_DEADLOCK
+            1213 => ErrorKind::Deadlock,
+            // ER_LOCK_WAIT_TIMEOUT, ER_QUERY_TIMEOUT
+            1205 | 3024 => ErrorKind::Timeout,
+            _ => ErrorKind::Other,
+        }
+    }
+
     #[doc(hidden)]
     fn as_error(&self) -> &(dyn Error + Send + Sync + 'static) {
         self
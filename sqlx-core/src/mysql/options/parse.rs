@@ -51,6 +51,14 @@ impl FromStr for MySqlConnectOptions {
                     options = options.ssl_ca(&*value);
                 }
 
+                "ssl-cert" | "sslcert" => {
+                    options = options.ssl_client_cert(&*value);
+                }
+
+                "ssl-key" | "sslkey" => {
+                    options = options.ssl_client_key(&*value);
+                }
+
                 "charset" => {
                     options = options.charset(&*value);
                 }
@@ -91,3 +99,18 @@ fn it_parses_password_with_non_ascii_chars_correctly() {
 
     assert_eq!(Some("p@ssw0rd".into()), opts.password);
 }
+
+#[test]
+fn it_parses_ssl_client_cert_and_key_correctly() {
+    let url = "mysql://localhost/db?ssl-cert=%2Fcerts%2Fclient.crt&ssl-key=%2Fcerts%2Fclient.key";
+    let opts = MySqlConnectOptions::from_str(url).unwrap();
+
+    assert!(matches!(
+        opts.ssl_client_cert,
+        Some(crate::net::CertificateInput::File(path)) if path == std::path::Path::new("/certs/client.crt")
+    ));
+    assert!(matches!(
+        opts.ssl_client_key,
+        Some(crate::net::CertificateInput::File(path)) if path == std::path::Path::new("/certs/client.key")
+    ));
+}
@@ -6,8 +6,8 @@ use crate::mysql::connection::Waiting;
 use crate::mysql::protocol::text::Query;
 use crate::mysql::{MySql, MySqlConnection};
 use crate::transaction::{
-    begin_ansi_transaction_sql, commit_ansi_transaction_sql, rollback_ansi_transaction_sql,
-    TransactionManager,
+    begin_ansi_transaction_sql, check_savepoint_options, commit_ansi_transaction_sql,
+    rollback_ansi_transaction_sql, IsolationLevel, TransactionManager, TransactionOptions,
 };
 
 /// Implementation of [`TransactionManager`] for MySQL.
@@ -17,16 +17,70 @@ impl TransactionManager for MySqlTransactionManager {
     type Database = MySql;
 
     fn begin(conn: &mut MySqlConnection) -> BoxFuture<'_, Result<(), Error>> {
+        Self::begin_with(conn, TransactionOptions::default())
+    }
+
+    fn begin_with(
+        conn: &mut MySqlConnection,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
             let depth = conn.transaction_depth;
 
-            conn.execute(&*begin_ansi_transaction_sql(depth)).await?;
+            check_savepoint_options(depth, &options)?;
+
+            if depth == 0 {
+                if let Some(level) = options.isolation_level {
+                    // applies to the next transaction only
+                    conn.execute(&*set_isolation_level_sql(level)).await?;
+                }
+
+                conn.execute(&*start_transaction_sql(&options)).await?;
+            } else {
+                conn.execute(&*begin_ansi_transaction_sql(depth)).await?;
+            }
+
             conn.transaction_depth = depth + 1;
 
             Ok(())
         })
     }
 
+    fn savepoint<'c>(
+        conn: &'c mut MySqlConnection,
+        name: &'c str,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        Box::pin(async move {
+            conn.execute(&*format!("SAVEPOINT {}", name)).await?;
+
+            Ok(())
+        })
+    }
+
+    fn rollback_to_savepoint<'c>(
+        conn: &'c mut MySqlConnection,
+        name: &'c str,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        Box::pin(async move {
+            conn.execute(&*format!("ROLLBACK TO SAVEPOINT {}", name))
+                .await?;
+
+            Ok(())
+        })
+    }
+
+    fn release_savepoint<'c>(
+        conn: &'c mut MySqlConnection,
+        name: &'c str,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        Box::pin(async move {
+            conn.execute(&*format!("RELEASE SAVEPOINT {}", name))
+                .await?;
+
+            Ok(())
+        })
+    }
+
     fn commit(conn: &mut MySqlConnection) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
             let depth = conn.transaction_depth;
@@ -66,3 +120,54 @@ impl TransactionManager for MySqlTransactionManager {
         }
     }
 }
+
+fn set_isolation_level_sql(level: IsolationLevel) -> String {
+    let level = match level {
+        // InnoDB implements REPEATABLE READ with a snapshot, see `start_transaction_sql`
+        IsolationLevel::Snapshot => IsolationLevel::RepeatableRead,
+        level => level,
+    };
+
+    format!("SET TRANSACTION ISOLATION LEVEL {}", level)
+}
+
+fn start_transaction_sql(options: &TransactionOptions) -> String {
+    let mut characteristics = Vec::new();
+
+    if options.isolation_level == Some(IsolationLevel::Snapshot) {
+        // take the snapshot now instead of with the first read
+        characteristics.push("WITH CONSISTENT SNAPSHOT");
+    }
+
+    if options.read_only {
+        characteristics.push("READ ONLY");
+    }
+
+    if characteristics.is_empty() {
+        "START TRANSACTION".to_owned()
+    } else {
+        format!("START TRANSACTION {}", characteristics.join(", "))
+    }
+}
+
+#[test]
+fn test_start_transaction_sql() {
+    assert_eq!(
+        start_transaction_sql(&TransactionOptions::new()),
+        "START TRANSACTION"
+    );
+
+    assert_eq!(
+        set_isolation_level_sql(IsolationLevel::Snapshot),
+        "SET TRANSACTION ISOLATION LEVEL REPEATABLE READ"
+    );
+
+    assert_eq!(
+        start_transaction_sql(
+            &TransactionOptions::new()
+                .isolation_level(IsolationLevel::Snapshot)
+                .read_only(true)
+        ),
+        "START TRANSACTION WITH CONSISTENT SNAPSHOT, READ ONLY"
+    );
+}
@@ -12,6 +12,8 @@ use crate::error::Error;
 use std::mem::replace;
 
 /// X.509 Certificate input, either a file path or a PEM encoded inline certificate(s).
+///
+/// Also used for the private key of a client certificate.
 #[derive(Clone, Debug)]
 pub enum CertificateInput {
     /// PEM encoded certificate(s)
@@ -24,8 +26,9 @@ impl From<String> for CertificateInput {
     fn from(value: String) -> Self {
         let trimmed = value.trim();
         // Some heuristics according to https://tools.ietf.org/html/rfc7468
-        if trimmed.starts_with("-----BEGIN CERTIFICATE-----")
-            && trimmed.contains("-----END CERTIFICATE-----")
+        if (trimmed.starts_with("-----BEGIN CERTIFICATE-----")
+            && trimmed.contains("-----END CERTIFICATE-----"))
+            || (trimmed.starts_with("-----BEGIN ") && trimmed.contains(" PRIVATE KEY-----"))
         {
             CertificateInput::Inline(value.as_bytes().to_vec())
         } else {
@@ -80,11 +83,25 @@ where
         accept_invalid_certs: bool,
         accept_invalid_hostnames: bool,
         root_cert_path: Option<&CertificateInput>,
+        client_cert_path: Option<&CertificateInput>,
+        client_key_path: Option<&CertificateInput>,
     ) -> Result<(), Error> {
+        let client_identity = match (client_cert_path, client_key_path) {
+            (Some(cert), Some(key)) => Some((cert, key)),
+            (None, None) => None,
+
+            _ => {
+                return Err(Error::Configuration(
+                    "a client certificate and its private key must be set together".into(),
+                ));
+            }
+        };
+
         let connector = configure_tls_connector(
             accept_invalid_certs,
             accept_invalid_hostnames,
             root_cert_path,
+            client_identity,
         )
         .await?;
 
@@ -117,8 +134,9 @@ async fn configure_tls_connector(
     accept_invalid_certs: bool,
     accept_invalid_hostnames: bool,
     root_cert_path: Option<&CertificateInput>,
+    client_identity: Option<(&CertificateInput, &CertificateInput)>,
 ) -> Result<sqlx_rt::TlsConnector, Error> {
-    use sqlx_rt::native_tls::{Certificate, TlsConnector};
+    use sqlx_rt::native_tls::{Certificate, Identity, TlsConnector};
 
     let mut builder = TlsConnector::builder();
     builder
@@ -134,6 +152,13 @@ async fn configure_tls_connector(
         }
     }
 
+    if let Some((cert, key)) = client_identity {
+        // the private key must be in PKCS #8 format
+        let identity = Identity::from_pkcs8(&cert.data().await?, &key.data().await?)?;
+
+        builder.identity(identity);
+    }
+
     #[cfg(not(feature = "_rt-async-std"))]
     let connector = builder.build()?.into();
 
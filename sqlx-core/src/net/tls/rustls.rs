@@ -3,6 +3,7 @@ use rustls::{
     client::{ServerCertVerified, ServerCertVerifier, WebPkiVerifier},
     ClientConfig, Error as TlsError, OwnedTrustAnchor, RootCertStore, ServerName,
 };
+use rustls_pemfile::Item;
 use std::io::Cursor;
 use std::sync::Arc;
 use std::time::SystemTime;
@@ -13,52 +14,101 @@ pub async fn configure_tls_connector(
     accept_invalid_certs: bool,
     accept_invalid_hostnames: bool,
     root_cert_path: Option<&CertificateInput>,
+    client_identity: Option<(&CertificateInput, &CertificateInput)>,
 ) -> Result<sqlx_rt::TlsConnector, Error> {
-    let config = ClientConfig::builder().with_safe_defaults();
+    let identity = match client_identity {
+        Some((cert, key)) => Some((load_certs(cert).await?, load_private_key(key).await?)),
+        None => None,
+    };
 
-    let config = if accept_invalid_certs {
-        config
-            .with_custom_certificate_verifier(Arc::new(DummyTlsVerifier))
-            .with_no_client_auth()
-    } else {
-        let mut cert_store = RootCertStore::empty();
-        cert_store.add_server_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.0.iter().map(|ta| {
-            OwnedTrustAnchor::from_subject_spki_name_constraints(
-                ta.subject,
-                ta.spki,
-                ta.name_constraints,
-            )
-        }));
-
-        if let Some(ca) = root_cert_path {
-            let data = ca.data().await?;
-            let mut cursor = Cursor::new(data);
-
-            for cert in rustls_pemfile::certs(&mut cursor)
-                .map_err(|_| Error::Tls(format!("Invalid certificate {}", ca).into()))?
-            {
-                cert_store
-                    .add(&rustls::Certificate(cert))
-                    .map_err(|err| Error::Tls(err.into()))?;
-            }
-        }
+    let builder = ClientConfig::builder().with_safe_defaults();
 
-        if accept_invalid_hostnames {
+    // the builder is in another state with a custom verifier than with root certificates,
+    // so the client certificate is set in each branch
+    let config = if accept_invalid_certs || accept_invalid_hostnames {
+        let verifier: Arc<dyn ServerCertVerifier> = if accept_invalid_certs {
+            Arc::new(DummyTlsVerifier)
+        } else {
+            let cert_store = load_root_certs(root_cert_path).await?;
             let verifier = WebPkiVerifier::new(cert_store, None);
 
-            config
-                .with_custom_certificate_verifier(Arc::new(NoHostnameTlsVerifier { verifier }))
-                .with_no_client_auth()
-        } else {
-            config
-                .with_root_certificates(cert_store)
-                .with_no_client_auth()
+            Arc::new(NoHostnameTlsVerifier { verifier })
+        };
+
+        let builder = builder.with_custom_certificate_verifier(verifier);
+
+        match identity {
+            Some((cert_chain, key)) => builder.with_single_cert(cert_chain, key),
+            None => Ok(builder.with_no_client_auth()),
+        }
+    } else {
+        let builder = builder.with_root_certificates(load_root_certs(root_cert_path).await?);
+
+        match identity {
+            Some((cert_chain, key)) => builder.with_single_cert(cert_chain, key),
+            None => Ok(builder.with_no_client_auth()),
         }
     };
 
+    let config = config.map_err(|err| Error::Tls(err.into()))?;
+
     Ok(Arc::new(config).into())
 }
 
+async fn load_root_certs(
+    root_cert_path: Option<&CertificateInput>,
+) -> Result<RootCertStore, Error> {
+    let mut cert_store = RootCertStore::empty();
+    cert_store.add_server_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.0.iter().map(|ta| {
+        OwnedTrustAnchor::from_subject_spki_name_constraints(
+            ta.subject,
+            ta.spki,
+            ta.name_constraints,
+        )
+    }));
+
+    if let Some(ca) = root_cert_path {
+        for cert in load_certs(ca).await? {
+            cert_store
+                .add(&cert)
+                .map_err(|err| Error::Tls(err.into()))?;
+        }
+    }
+
+    Ok(cert_store)
+}
+
+async fn load_certs(input: &CertificateInput) -> Result<Vec<rustls::Certificate>, Error> {
+    let data = input.data().await?;
+    let mut cursor = Cursor::new(data);
+
+    let certs = rustls_pemfile::certs(&mut cursor)
+        .map_err(|_| Error::Tls(format!("Invalid certificate {}", input).into()))?;
+
+    Ok(certs.into_iter().map(rustls::Certificate).collect())
+}
+
+async fn load_private_key(input: &CertificateInput) -> Result<rustls::PrivateKey, Error> {
+    let data = input.data().await?;
+    let mut cursor = Cursor::new(data);
+
+    loop {
+        match rustls_pemfile::read_one(&mut cursor) {
+            Ok(Some(Item::RSAKey(key) | Item::PKCS8Key(key) | Item::ECKey(key))) => {
+                return Ok(rustls::PrivateKey(key))
+            }
+
+            // skip anything else, such as certificates bundled with the key
+            Ok(Some(_)) => {}
+
+            // don't print the key itself, unlike certificates it is a secret
+            Ok(None) | Err(_) => {
+                return Err(Error::Tls("no private key found in the client key".into()));
+            }
+        }
+    }
+}
+
 struct DummyTlsVerifier;
 
 impl ServerCertVerifier for DummyTlsVerifier {
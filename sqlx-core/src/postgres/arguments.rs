@@ -166,6 +166,11 @@ impl PgArgumentBuffer {
         self.extend_from_slice(&0_u32.to_be_bytes());
         self.type_holes.push((offset, type_name.clone()));
     }
+
+    // The names of the types whose OIDs are still to be patched in
+    pub(crate) fn type_hole_names(&self) -> impl Iterator<Item = &UStr> {
+        self.type_holes.iter().map(|(_, name)| name)
+    }
 }
 
 impl Deref for PgArgumentBuffer {
@@ -0,0 +1,674 @@
+use std::cmp;
+use std::ops::DerefMut;
+
+use bytes::{Buf, Bytes, BytesMut};
+use futures_core::stream::BoxStream;
+use futures_util::TryStreamExt;
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::error::{Error, Result};
+use crate::io::Decode as _;
+use crate::pool::{Pool, PoolConnection};
+use crate::postgres::copy::{pg_begin_copy_out, PgCopyIn};
+use crate::postgres::message::DataRow;
+use crate::postgres::{PgArguments, PgConnection, PgValueFormat, PgValueRef, Postgres};
+use crate::types::Type;
+
+// https://www.postgresql.org/docs/current/sql-copy.html#id-1.9.3.55.9.4
+
+// signature, flags field and header extension area length
+const HEADER: &[u8] = b"PGCOPY\n\xff\r\n\0\0\0\0\0\0\0\0\0";
+const SIGNATURE_LEN: usize = 11;
+
+// a field count of -1
+const TRAILER: &[u8] = b"\xff\xff";
+
+// rows are sent to the server in chunks of about this size
+const CHUNK_SIZE: usize = 64 * 1024;
+
+impl PgConnection {
+    /// Issue a `COPY ... FROM STDIN (FORMAT BINARY)` statement, and write rows to it
+    /// with [`PgBinaryCopyIn::write_row`], which encodes them in the binary `COPY` format.
+    ///
+    /// The statement is executed when the first rows are sent, so an invalid statement is
+    /// reported by [`write_row`](PgBinaryCopyIn::write_row) or
+    /// [`finish`](PgBinaryCopyIn::finish).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::error::Error;
+    /// # use sqlx_core::connection::Connection;
+    /// # use sqlx_core::postgres::PgConnection;
+    /// #
+    /// # fn main() {
+    /// # #[cfg(feature = "_rt-async-std")]
+    /// # sqlx_rt::async_std::task::block_on::<_, Result<(), Error>>(async move {
+    /// let mut conn = PgConnection::connect("postgres://localhost/mydb").await?;
+    ///
+    /// let mut copy = conn.copy_in_binary("COPY users (id, name) FROM STDIN (FORMAT BINARY)");
+    ///
+    /// copy.write_row((1_i64, "alice")).await?;
+    /// copy.write_row((2_i64, "bob")).await?;
+    ///
+    /// let rows = copy.finish().await?;
+    /// assert_eq!(rows, 2);
+    /// # Ok(())
+    /// # }).unwrap();
+    /// # }
+    /// ```
+    pub fn copy_in_binary(&mut self, statement: &str) -> PgBinaryCopyIn<&mut Self> {
+        PgBinaryCopyIn::new(self, statement)
+    }
+
+    /// Issue a `COPY ... TO STDOUT (FORMAT BINARY)` statement and decode the data
+    /// streamed from Postgres into records.
+    ///
+    /// The same caveats as for [`copy_out_raw`](Self::copy_out_raw) apply.
+    pub async fn copy_out_binary<'c>(
+        &'c mut self,
+        statement: &str,
+    ) -> Result<BoxStream<'c, Result<PgCopyRecord>>> {
+        Ok(decode_copy_out(pg_begin_copy_out(self, statement).await?))
+    }
+}
+
+impl Pool<Postgres> {
+    /// Issue a `COPY ... FROM STDIN (FORMAT BINARY)` statement, and write rows to it
+    /// with [`PgBinaryCopyIn::write_row`].
+    ///
+    /// A single connection will be checked out for the duration.
+    pub async fn copy_in_binary(
+        &self,
+        statement: &str,
+    ) -> Result<PgBinaryCopyIn<PoolConnection<Postgres>>> {
+        Ok(PgBinaryCopyIn::new(self.acquire().await?, statement))
+    }
+
+    /// Issue a `COPY ... TO STDOUT (FORMAT BINARY)` statement and decode the data
+    /// streamed from Postgres into records.
+    ///
+    /// A single connection will be checked out for the duration.
+    pub async fn copy_out_binary(
+        &self,
+        statement: &str,
+    ) -> Result<BoxStream<'static, Result<PgCopyRecord>>> {
+        Ok(decode_copy_out(
+            pg_begin_copy_out(self.acquire().await?, statement).await?,
+        ))
+    }
+}
+
+/// A row that can be written with [`PgBinaryCopyIn::write_row`].
+///
+/// This is implemented for tuples of up to 16 values, and can be implemented for other types
+/// by encoding each column in order.
+///
+/// ```rust
+/// # use sqlx_core::postgres::{PgCopyRow, PgCopyRowEncoder};
+/// struct User {
+///     id: i64,
+///     name: String,
+/// }
+///
+/// impl<'q> PgCopyRow<'q> for &'q User {
+///     fn encode_row(self, encoder: &mut PgCopyRowEncoder<'_>) {
+///         encoder.encode(self.id).encode(&self.name);
+///     }
+/// }
+/// ```
+pub trait PgCopyRow<'q> {
+    /// Encode the columns of the row.
+    fn encode_row(self, encoder: &mut PgCopyRowEncoder<'_>);
+}
+
+/// Encodes the columns of a [`PgCopyRow`].
+pub struct PgCopyRowEncoder<'a> {
+    arguments: &'a mut PgArguments,
+    num_columns: usize,
+}
+
+impl PgCopyRowEncoder<'_> {
+    /// Encode the next column of the row.
+    ///
+    /// The value is encoded as its own Postgres type, which must match the type of the column.
+    pub fn encode<'q, T>(&mut self, value: T) -> &mut Self
+    where
+        T: Encode<'q, Postgres> + Type<Postgres>,
+    {
+        self.arguments.add(value);
+        self.num_columns += 1;
+
+        self
+    }
+}
+
+macro_rules! impl_copy_row_for_tuple {
+    ($( ($idx:tt) -> $T:ident );+;) => {
+        impl<'q, $($T,)+> PgCopyRow<'q> for ($($T,)+)
+        where
+            $($T: Encode<'q, Postgres> + Type<Postgres>,)+
+        {
+            #[inline]
+            fn encode_row(self, encoder: &mut PgCopyRowEncoder<'_>) {
+                $(encoder.encode(self.$idx);)+
+            }
+        }
+    };
+}
+
+impl_copy_row_for_tuple!(
+    (0) -> T1;
+);
+
+impl_copy_row_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+);
+
+impl_copy_row_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+);
+
+impl_copy_row_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+);
+
+impl_copy_row_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+);
+
+impl_copy_row_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+);
+
+impl_copy_row_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+);
+
+impl_copy_row_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+);
+
+impl_copy_row_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+);
+
+impl_copy_row_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+);
+
+impl_copy_row_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+    (10) -> T11;
+);
+
+impl_copy_row_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+    (10) -> T11;
+    (11) -> T12;
+);
+
+impl_copy_row_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+    (10) -> T11;
+    (11) -> T12;
+    (12) -> T13;
+);
+
+impl_copy_row_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+    (10) -> T11;
+    (11) -> T12;
+    (12) -> T13;
+    (13) -> T14;
+);
+
+impl_copy_row_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+    (10) -> T11;
+    (11) -> T12;
+    (12) -> T13;
+    (13) -> T14;
+    (14) -> T15;
+);
+
+impl_copy_row_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+    (10) -> T11;
+    (11) -> T12;
+    (12) -> T13;
+    (13) -> T14;
+    (14) -> T15;
+    (15) -> T16;
+);
+
+/// A `COPY FROM STDIN` in the binary format, which encodes the rows written to it.
+///
+/// Created by [`PgConnection::copy_in_binary`] or [`Pool::copy_in_binary`].
+///
+/// ### Note
+/// [`finish`](Self::finish) or [`abort`](Self::abort) *must* be called when finished,
+/// or none of the rows are copied.
+#[must_use = "rows are not copied if `.finish()` is not called"]
+pub struct PgBinaryCopyIn<C: DerefMut<Target = PgConnection>> {
+    conn: Option<C>,
+    statement: String,
+    copy: Option<PgCopyIn<C>>,
+
+    // the rows that were not sent yet
+    rows: PgArguments,
+}
+
+impl<C: DerefMut<Target = PgConnection>> PgBinaryCopyIn<C> {
+    fn new(conn: C, statement: &str) -> Self {
+        Self {
+            conn: Some(conn),
+            statement: statement.to_owned(),
+            copy: None,
+            rows: PgArguments::default(),
+        }
+    }
+
+    /// Encode a row and queue it to be sent to the server.
+    ///
+    /// The values of the row are encoded as their own Postgres types, which must match the
+    /// types of the columns being copied to. The server rejects the data otherwise.
+    pub async fn write_row<'q, R>(&mut self, row: R) -> Result<&mut Self>
+    where
+        R: PgCopyRow<'q>,
+    {
+        // reserve space for the number of columns
+        let offset = self.rows.buffer.len();
+        self.rows.buffer.extend(&[0; 2]);
+
+        let mut encoder = PgCopyRowEncoder {
+            arguments: &mut self.rows,
+            num_columns: 0,
+        };
+
+        row.encode_row(&mut encoder);
+
+        let num_columns = i16::try_from(encoder.num_columns)
+            .map_err(|_| err_protocol!("too many columns in a row: {}", encoder.num_columns))?;
+
+        self.rows.buffer[offset..(offset + 2)].copy_from_slice(&num_columns.to_be_bytes());
+
+        if self.rows.buffer.len() >= CHUNK_SIZE {
+            self.send_rows().await?;
+        }
+
+        Ok(self)
+    }
+
+    /// Send the remaining rows and signal that the `COPY` is complete.
+    ///
+    /// The number of rows copied is returned.
+    pub async fn finish(mut self) -> Result<u64> {
+        self.send_rows().await?;
+
+        let mut copy = self.copy.take().ok_or_else(not_started)?;
+        copy.send(TRAILER).await?;
+
+        copy.finish().await
+    }
+
+    /// Signal that the `COPY` should be aborted, and discard any rows that were sent.
+    ///
+    /// See [`PgCopyIn::abort`].
+    pub async fn abort(mut self, msg: impl Into<String>) -> Result<()> {
+        match self.copy.take() {
+            Some(copy) => copy.abort(msg).await,
+
+            // nothing was sent yet
+            None => Ok(()),
+        }
+    }
+
+    async fn send_rows(&mut self) -> Result<()> {
+        // there is no statement, so patches are resolved against the types of the values
+        let types = std::mem::take(&mut self.rows.types);
+
+        match &mut self.copy {
+            None => {
+                // the connection is gone once the `COPY` failed to start
+                let mut conn = self.conn.take().ok_or_else(not_started)?;
+
+                // the OIDs of types referenced by name must be known before the `COPY` begins
+                self.rows.apply_patches(&mut *conn, &types).await?;
+
+                let mut copy = PgCopyIn::begin(conn, &self.statement).await?;
+
+                if copy.is_textual() {
+                    return Err(err_protocol!(
+                        "expected a COPY in the binary format, use `COPY ... FROM STDIN (FORMAT BINARY)`"
+                    ));
+                }
+
+                copy.send(HEADER).await?;
+                self.copy = Some(copy);
+            }
+
+            Some(copy) => {
+                // no other query can be run during the `COPY`, so only the OIDs of types
+                // that were already looked up are available
+                let conn = copy.conn_mut();
+
+                if let Some(name) = self
+                    .rows
+                    .buffer
+                    .type_hole_names()
+                    .find(|name| !conn.cache_type_oid.contains_key(*name))
+                {
+                    return Err(Error::TypeNotFound {
+                        type_name: name.to_string(),
+                    });
+                }
+
+                self.rows.apply_patches(conn, &types).await?;
+            }
+        }
+
+        if !self.rows.buffer.is_empty() {
+            let copy = self.copy.as_mut().unwrap();
+            let rows = std::mem::take(&mut self.rows);
+
+            copy.send(&**rows.buffer).await?;
+        }
+
+        Ok(())
+    }
+}
+
+// returned by every call after the `COPY` failed to start, which released the connection
+fn not_started() -> Error {
+    err_protocol!("the COPY failed to start, so no rows can be written to it")
+}
+
+/// A row decoded from a binary `COPY TO STDOUT`.
+///
+/// Unlike a [`PgRow`](crate::postgres::PgRow), the binary `COPY` format carries no type
+/// information, so values are decoded as the requested type without checking that it matches
+/// the type of the column.
+pub struct PgCopyRecord {
+    data: DataRow,
+}
+
+impl PgCopyRecord {
+    /// The number of columns in the row.
+    pub fn len(&self) -> usize {
+        self.data.values.len()
+    }
+
+    /// Returns `true` if the row has no columns.
+    pub fn is_empty(&self) -> bool {
+        self.data.values.is_empty()
+    }
+
+    /// Decode the value of a column.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds, or if the value cannot be decoded.
+    /// See [`try_get`](Self::try_get) for a non-panicking version.
+    pub fn get<'r, T>(&'r self, index: usize) -> T
+    where
+        T: Decode<'r, Postgres> + Type<Postgres>,
+    {
+        self.try_get(index).unwrap()
+    }
+
+    /// Decode the value of a column.
+    ///
+    /// # Errors
+    ///
+    ///  * [`ColumnIndexOutOfBounds`] if the index is greater than the number of columns.
+    ///  * [`ColumnDecode`] if the value could not be decoded into the requested type.
+    ///
+    /// [`ColumnIndexOutOfBounds`]: Error::ColumnIndexOutOfBounds
+    /// [`ColumnDecode`]: Error::ColumnDecode
+    pub fn try_get<'r, T>(&'r self, index: usize) -> Result<T>
+    where
+        T: Decode<'r, Postgres> + Type<Postgres>,
+    {
+        if index >= self.len() {
+            return Err(Error::ColumnIndexOutOfBounds {
+                index,
+                len: self.len(),
+            });
+        }
+
+        let value = PgValueRef {
+            value: self.data.get(index),
+            row: Some(&self.data.storage),
+            type_info: T::type_info(),
+            format: PgValueFormat::Binary,
+        };
+
+        T::decode(value).map_err(|source| Error::ColumnDecode {
+            index: format!("{:?}", index),
+            source,
+        })
+    }
+}
+
+fn decode_copy_out<'c>(
+    mut data: BoxStream<'c, Result<Bytes>>,
+) -> BoxStream<'c, Result<PgCopyRecord>> {
+    Box::pin(try_stream! {
+        let mut buf = BytesMut::new();
+        let mut header = false;
+
+        while let Some(chunk) = data.try_next().await? {
+            buf.extend_from_slice(&chunk);
+
+            if !header {
+                if !decode_header(&mut buf)? {
+                    continue;
+                }
+
+                header = true;
+            }
+
+            while let Some(record) = decode_record(&mut buf)? {
+                match record {
+                    Some(record) => r#yield!(record),
+
+                    // the server sends nothing after the trailer
+                    None => return Ok(()),
+                }
+            }
+        }
+
+        Err(err_protocol!("binary COPY data ended without a trailer"))
+    })
+}
+
+// returns `false` if more data is needed
+fn decode_header(buf: &mut BytesMut) -> Result<bool> {
+    if buf.len() < HEADER.len() {
+        return Ok(false);
+    }
+
+    if buf[..SIGNATURE_LEN] != HEADER[..SIGNATURE_LEN] {
+        return Err(err_protocol!(
+            "expected COPY data in the binary format, use `COPY ... TO STDOUT (FORMAT BINARY)`"
+        ));
+    }
+
+    // the header extension area is skipped, as no extensions are defined yet
+    let extension_len = (&buf[(SIGNATURE_LEN + 4)..]).get_u32() as usize;
+
+    if buf.len() < HEADER.len() + extension_len {
+        return Ok(false);
+    }
+
+    buf.advance(HEADER.len() + extension_len);
+
+    Ok(true)
+}
+
+// returns `None` if more data is needed, and `Some(None)` for the trailer
+fn decode_record(buf: &mut BytesMut) -> Result<Option<Option<PgCopyRecord>>> {
+    if buf.len() < 2 {
+        return Ok(None);
+    }
+
+    let num_columns = (&buf[..]).get_i16();
+
+    if num_columns == -1 {
+        buf.advance(2);
+
+        return Ok(Some(None));
+    }
+
+    // find the end of the row before splitting it off
+    let mut len = 2;
+
+    for _ in 0..num_columns {
+        if buf.len() < len + 4 {
+            return Ok(None);
+        }
+
+        let value_len = (&buf[len..]).get_i32();
+        len += 4 + cmp::max(value_len, 0) as usize;
+    }
+
+    if buf.len() < len {
+        return Ok(None);
+    }
+
+    // the tuple has the same layout as a `DataRow` message
+    let data = DataRow::decode(buf.split_to(len).freeze())?;
+
+    Ok(Some(Some(PgCopyRecord { data })))
+}
+
+#[test]
+fn test_decode_copy_out() {
+    const DATA: &[u8] = b"PGCOPY\n\xff\r\n\0\0\0\0\0\0\0\0\0\
+        \0\x02\0\0\0\x04\0\0\0\x01\xff\xff\xff\xff\
+        \0\x02\0\0\0\x04\0\0\0\x02\0\0\0\x03abc\
+        \xff\xff";
+
+    let mut buf = BytesMut::from(DATA);
+
+    assert!(decode_header(&mut buf).unwrap());
+
+    let first = decode_record(&mut buf).unwrap().unwrap().unwrap();
+    assert_eq!(first.len(), 2);
+    assert_eq!(first.get::<i32>(0), 1);
+    assert_eq!(first.get::<Option<String>>(1), None);
+
+    // a row split across chunks is decoded once all of it arrived
+    let mut partial = buf.split_to(10);
+    assert!(decode_record(&mut partial).unwrap().is_none());
+    partial.unsplit(buf);
+    let mut buf = partial;
+
+    let second = decode_record(&mut buf).unwrap().unwrap().unwrap();
+    assert_eq!(second.get::<i32>(0), 2);
+    assert_eq!(second.get::<&str>(1), "abc");
+
+    assert!(decode_record(&mut buf).unwrap().unwrap().is_none());
+    assert!(buf.is_empty());
+}
@@ -0,0 +1,112 @@
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use bytes::Bytes;
+
+use crate::error::Error;
+use crate::postgres::connection::{stream::PgStream, tls};
+use crate::postgres::message::CancelRequest;
+use crate::postgres::{PgConnectOptions, PgConnection};
+
+// https://www.postgresql.org/docs/current/protocol-flow.html#id-1.10.5.7.9
+
+/// A handle that can cancel the query currently running on a [`PgConnection`], from
+/// anywhere, without needing access to the connection itself.
+///
+/// Obtained from [`PgConnection::cancel_token`].
+#[derive(Clone)]
+pub struct PgCancelToken {
+    options: Arc<PgConnectOptions>,
+    process_id: u32,
+    secret_key: u32,
+}
+
+impl PgCancelToken {
+    pub(super) fn new(options: &PgConnectOptions, process_id: u32, secret_key: u32) -> Self {
+        Self {
+            options: Arc::new(options.clone()),
+            process_id,
+            secret_key,
+        }
+    }
+
+    /// The process ID of the backend this token cancels queries on.
+    pub fn process_id(&self) -> u32 {
+        self.process_id
+    }
+
+    /// Ask the server to cancel the query that the connection is currently running.
+    ///
+    /// This opens a new connection to the server to send the request. Cancellation is
+    /// best-effort: nothing happens if the connection is idle, and the query may still
+    /// complete if it finishes before the request is processed. If the query is cancelled,
+    /// it returns an error with the code `57014` (`query_canceled`) on its connection.
+    ///
+    /// Note that the request cancels whatever the connection is running when it arrives,
+    /// which may be a later query than the one that was intended.
+    pub async fn cancel(&self) -> Result<(), Error> {
+        let mut stream = PgStream::connect(&self.options).await?;
+
+        tls::maybe_upgrade(&mut stream, &self.options).await?;
+
+        stream
+            .send(CancelRequest {
+                process_id: self.process_id,
+                secret_key: self.secret_key,
+            })
+            .await?;
+
+        // the server does not reply, but closes the connection once it processed the request
+        let _ = stream.read::<Bytes>(1).await;
+        let _ = stream.shutdown().await;
+
+        Ok(())
+    }
+}
+
+impl Debug for PgCancelToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PgCancelToken")
+            .field("process_id", &self.process_id)
+            .finish()
+    }
+}
+
+impl PgConnection {
+    /// Returns a token that can cancel the query this connection is running, for example
+    /// from another task while this one is waiting on the result.
+    ///
+    /// This is also available on a [`PoolConnection<Postgres>`] through `Deref`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::error::Error;
+    /// # use sqlx_core::connection::Connection;
+    /// # use sqlx_core::executor::Executor;
+    /// # use sqlx_core::postgres::PgConnection;
+    /// #
+    /// # fn main() {
+    /// # #[cfg(feature = "_rt-async-std")]
+    /// # sqlx_rt::async_std::task::block_on::<_, Result<(), Error>>(async move {
+    /// let mut conn = PgConnection::connect("postgres://localhost/mydb").await?;
+    /// let token = conn.cancel_token();
+    ///
+    /// sqlx_rt::spawn(async move {
+    ///     sqlx_rt::sleep(std::time::Duration::from_secs(1)).await;
+    ///     let _ = token.cancel().await;
+    /// });
+    ///
+    /// // fails with `57014` (`query_canceled`)
+    /// let res = conn.execute("SELECT pg_sleep(10)").await;
+    /// assert!(res.is_err());
+    /// # Ok(())
+    /// # }).unwrap();
+    /// # }
+    /// ```
+    ///
+    /// [`PoolConnection<Postgres>`]: crate::pool::PoolConnection
+    pub fn cancel_token(&self) -> PgCancelToken {
+        self.cancel_token.clone()
+    }
+}
@@ -1,20 +1,141 @@
+use std::borrow::Cow;
+
 use crate::HashMap;
+use rand::seq::SliceRandom;
 
 use crate::common::StatementCache;
+use crate::connection::Connection;
 use crate::error::Error;
+use crate::executor::Executor;
 use crate::io::Decode;
-use crate::postgres::connection::{sasl, stream::PgStream, tls};
+use crate::postgres::connection::{sasl, stream::PgStream, tls, PgCancelToken};
 use crate::postgres::message::{
     Authentication, BackendKeyData, MessageFormat, Password, ReadyForQuery, Startup,
 };
 use crate::postgres::types::Oid;
-use crate::postgres::{PgConnectOptions, PgConnection};
+use crate::postgres::{PgConnectOptions, PgConnection, PgTargetSessionAttrs};
+use crate::row::Row;
 
 // https://www.postgresql.org/docs/current/protocol-flow.html#id-1.10.5.7.3
 // https://www.postgresql.org/docs/current/protocol-flow.html#id-1.10.5.7.11
 
 impl PgConnection {
     pub(crate) async fn establish(options: &PgConnectOptions) -> Result<Self, Error> {
+        if options.fallback_hosts.is_empty()
+            && options.target_session_attrs == PgTargetSessionAttrs::Any
+        {
+            return Self::establish_host(options).await;
+        }
+
+        let mut hosts = vec![Cow::Borrowed(options)];
+
+        for (host, port) in &options.fallback_hosts {
+            let mut host_options = options.clone();
+            host_options.host = host.clone();
+            host_options.port = port.unwrap_or(options.port);
+            host_options.socket = None;
+
+            hosts.push(Cow::Owned(host_options));
+        }
+
+        if options.load_balance_hosts {
+            hosts.shuffle(&mut rand::thread_rng());
+        }
+
+        // https://www.postgresql.org/docs/current/libpq-connect.html#LIBPQ-CONNECT-TARGET-SESSION-ATTRS
+        let passes: &[PgTargetSessionAttrs] = match options.target_session_attrs {
+            PgTargetSessionAttrs::PreferStandby => {
+                &[PgTargetSessionAttrs::Standby, PgTargetSessionAttrs::Any]
+            }
+
+            ref attrs => std::slice::from_ref(attrs),
+        };
+
+        let mut last_error = None;
+
+        for &attrs in passes {
+            for host in &hosts {
+                let mut conn = match Self::establish_host(host).await {
+                    Ok(conn) => conn,
+
+                    Err(error) => {
+                        log::debug!(
+                            "failed to connect to {}:{}: {}",
+                            host.host,
+                            host.port,
+                            error
+                        );
+                        last_error = Some(error);
+
+                        continue;
+                    }
+                };
+
+                match conn.matches_session_attrs(attrs).await {
+                    Ok(true) => return Ok(conn),
+
+                    Ok(false) => {
+                        let _ = conn.close().await;
+
+                        last_error = Some(Error::Configuration(
+                            format!(
+                                "none of the hosts matched target_session_attrs={}",
+                                options.target_session_attrs
+                            )
+                            .into(),
+                        ));
+                    }
+
+                    Err(error) => last_error = Some(error),
+                }
+            }
+        }
+
+        Err(last_error.expect("there is at least one host"))
+    }
+
+    async fn matches_session_attrs(&mut self, attrs: PgTargetSessionAttrs) -> Result<bool, Error> {
+        Ok(match attrs {
+            PgTargetSessionAttrs::Any => true,
+            PgTargetSessionAttrs::ReadWrite => !self.is_read_only().await?,
+            PgTargetSessionAttrs::ReadOnly => self.is_read_only().await?,
+            PgTargetSessionAttrs::Primary => !self.is_in_hot_standby().await?,
+            PgTargetSessionAttrs::Standby | PgTargetSessionAttrs::PreferStandby => {
+                self.is_in_hot_standby().await?
+            }
+        })
+    }
+
+    async fn is_read_only(&mut self) -> Result<bool, Error> {
+        let statuses = &self.stream.parameter_statuses;
+
+        // both are reported at startup by Postgres 14 and later
+        if let (Some(read_only), Some(hot_standby)) = (
+            statuses.get("default_transaction_read_only"),
+            statuses.get("in_hot_standby"),
+        ) {
+            return Ok(read_only == "on" || hot_standby == "on");
+        }
+
+        let row = self.fetch_one("SHOW transaction_read_only").await?;
+
+        Ok(row.try_get::<String, _>(0)? == "on")
+    }
+
+    async fn is_in_hot_standby(&mut self) -> Result<bool, Error> {
+        // reported at startup by Postgres 14 and later
+        if let Some(hot_standby) = self.stream.parameter_statuses.get("in_hot_standby") {
+            return Ok(hot_standby == "on");
+        }
+
+        let row = self
+            .fetch_one("SELECT pg_catalog.pg_is_in_recovery()")
+            .await?;
+
+        row.try_get(0)
+    }
+
+    async fn establish_host(options: &PgConnectOptions) -> Result<Self, Error> {
         let mut stream = PgStream::connect(options).await?;
 
         // Upgrade to TLS if we were asked to and the server supports it
@@ -46,6 +167,11 @@ impl PgConnection {
             params.push(("options", options));
         }
 
+        if options.replication {
+            // logical replication, bound to the database
+            params.push(("replication", "database"));
+        }
+
         stream
             .send(Startup {
                 username: Some(&options.username),
@@ -139,11 +265,12 @@ impl PgConnection {
 
         Ok(PgConnection {
             stream,
-            process_id,
-            secret_key,
+            cancel_token: PgCancelToken::new(options, process_id, secret_key),
+            cancel_on_drop: options.cancel_on_drop,
             transaction_status,
             transaction_depth: 0,
             pending_ready_for_query_count: 0,
+            query_in_progress: false,
             next_statement_id: Oid(1),
             cache_statement: StatementCache::new(options.statement_cache_capacity),
             cache_type_oid: HashMap::new(),
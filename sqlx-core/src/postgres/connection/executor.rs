@@ -160,7 +160,7 @@ impl PgConnection {
         self.pending_ready_for_query_count += 1;
     }
 
-    async fn get_or_prepare<'a>(
+    pub(in crate::postgres) async fn get_or_prepare<'a>(
         &mut self,
         sql: &str,
         parameters: &[PgTypeInfo],
@@ -270,10 +270,20 @@ impl PgConnection {
         };
 
         self.stream.flush().await?;
+        self.query_in_progress = true;
 
         Ok(try_stream! {
             loop {
-                let message = self.stream.recv().await?;
+                let message = match self.stream.recv().await {
+                    Ok(message) => message,
+
+                    Err(error) => {
+                        // the query is over, even though it failed
+                        self.query_in_progress = false;
+
+                        return Err(error);
+                    }
+                };
 
                 match message.format {
                     MessageFormat::BindComplete
@@ -339,6 +349,7 @@ impl PgConnection {
 
                     MessageFormat::ReadyForQuery => {
                         // processing of the query string is complete
+                        self.query_in_progress = false;
                         self.handle_ready_for_query(message)?;
                         break;
                     }
@@ -399,16 +410,27 @@ impl<'c> Executor<'c> for &'c mut PgConnection {
         let persistent = query.persistent();
 
         Box::pin(async move {
-            let s = self.run(sql, arguments, 1, persistent, metadata).await?;
-            pin_mut!(s);
+            let row = {
+                let s = self.run(sql, arguments, 1, persistent, metadata).await?;
+                pin_mut!(s);
+
+                let mut row = None;
 
-            while let Some(s) = s.try_next().await? {
-                if let Either::Right(r) = s {
-                    return Ok(Some(r));
+                while let Some(s) = s.try_next().await? {
+                    if let Either::Right(r) = s {
+                        row = Some(r);
+                        break;
+                    }
                 }
-            }
 
-            Ok(None)
+                row
+            };
+
+            // the row was received, so the rest of the query is not abandoned
+            // and is left to complete as usual
+            self.query_in_progress = false;
+
+            Ok(row)
         })
     }
 
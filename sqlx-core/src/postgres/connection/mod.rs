@@ -16,10 +16,12 @@ use crate::postgres::message::{
 use crate::postgres::statement::PgStatementMetadata;
 use crate::postgres::types::Oid;
 use crate::postgres::{PgConnectOptions, PgTypeInfo, Postgres};
-use crate::transaction::Transaction;
+use crate::transaction::{Transaction, TransactionOptions};
 
+pub use self::cancel::PgCancelToken;
 pub use self::stream::PgStream;
 
+mod cancel;
 pub(crate) mod describe;
 mod establish;
 mod executor;
@@ -34,35 +36,36 @@ pub struct PgConnection {
     // wrapped in a buffered stream
     pub(crate) stream: PgStream,
 
-    // process id of this backend
+    // process id and secret key of this backend
     // used to send cancel requests
-    #[allow(dead_code)]
-    process_id: u32,
+    cancel_token: PgCancelToken,
 
-    // secret key of this backend
-    // used to send cancel requests
-    #[allow(dead_code)]
-    secret_key: u32,
+    // cancel a query that was abandoned before it completed,
+    // the next time this connection is used
+    cancel_on_drop: bool,
 
     // sequence of statement IDs for use in preparing statements
     // in PostgreSQL, the statement is prepared to a user-supplied identifier
     next_statement_id: Oid,
 
     // cache statement by query string to the id and columns
-    cache_statement: StatementCache<(Oid, Arc<PgStatementMetadata>)>,
+    pub(in crate::postgres) cache_statement: StatementCache<(Oid, Arc<PgStatementMetadata>)>,
 
     // cache user-defined types by id <-> info
     cache_type_info: HashMap<Oid, PgTypeInfo>,
-    cache_type_oid: HashMap<UStr, Oid>,
+    pub(in crate::postgres) cache_type_oid: HashMap<UStr, Oid>,
 
     // number of ReadyForQuery messages that we are currently expecting
     pub(crate) pending_ready_for_query_count: usize,
 
+    // set while the results of a query are being received
+    pub(in crate::postgres) query_in_progress: bool,
+
     // current transaction status
     transaction_status: TransactionStatus,
     pub(crate) transaction_depth: usize,
 
-    log_settings: LogSettings,
+    pub(in crate::postgres) log_settings: LogSettings,
 }
 
 impl PgConnection {
@@ -73,6 +76,18 @@ impl PgConnection {
 
     // will return when the connection is ready for another query
     pub(in crate::postgres) async fn wait_until_ready(&mut self) -> Result<(), Error> {
+        if self.query_in_progress {
+            // the future or stream running the last query was dropped before it completed
+            self.query_in_progress = false;
+
+            // this must happen before anything queued is sent, so it cannot be cancelled instead
+            if self.cancel_on_drop {
+                if let Err(error) = self.cancel_token.cancel().await {
+                    log::warn!("failed to cancel an abandoned query: {}", error);
+                }
+            }
+        }
+
         if !self.stream.wbuf.is_empty() {
             self.stream.flush().await?;
         }
@@ -88,7 +103,7 @@ impl PgConnection {
         Ok(())
     }
 
-    async fn recv_ready_for_query(&mut self) -> Result<(), Error> {
+    pub(in crate::postgres) async fn recv_ready_for_query(&mut self) -> Result<(), Error> {
         let r: ReadyForQuery = self
             .stream
             .recv_expect(MessageFormat::ReadyForQuery)
@@ -169,6 +184,16 @@ impl Connection for PgConnection {
         Transaction::begin(self)
     }
 
+    fn begin_with(
+        &mut self,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
+    where
+        Self: Sized,
+    {
+        Transaction::begin_with(self, options)
+    }
+
     fn cached_statements_size(&self) -> usize {
         self.cache_statement.len()
     }
@@ -116,7 +116,7 @@ pub struct PgCopyIn<C: DerefMut<Target = PgConnection>> {
 }
 
 impl<C: DerefMut<Target = PgConnection>> PgCopyIn<C> {
-    async fn begin(mut conn: C, statement: &str) -> Result<Self> {
+    pub(super) async fn begin(mut conn: C, statement: &str) -> Result<Self> {
         conn.wait_until_ready().await?;
         conn.stream.send(Query(statement)).await?;
 
@@ -154,6 +154,12 @@ impl<C: DerefMut<Target = PgConnection>> PgCopyIn<C> {
         self.response.format_codes[column] == 0
     }
 
+    pub(super) fn conn_mut(&mut self) -> &mut PgConnection {
+        self.conn
+            .as_deref_mut()
+            .expect("PgCopyIn::conn_mut: conn taken illegally")
+    }
+
     /// Send a chunk of `COPY` data.
     ///
     /// If you're copying data from an `AsyncRead`, maybe consider [Self::read_from] instead.
@@ -301,7 +307,7 @@ impl<C: DerefMut<Target = PgConnection>> Drop for PgCopyIn<C> {
     }
 }
 
-async fn pg_begin_copy_out<'c, C: DerefMut<Target = PgConnection> + Send + 'c>(
+pub(super) async fn pg_begin_copy_out<'c, C: DerefMut<Target = PgConnection> + Send + 'c>(
     mut conn: C,
     statement: &str,
 ) -> Result<BoxStream<'c, Result<Bytes>>> {
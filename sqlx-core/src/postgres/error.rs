@@ -4,7 +4,7 @@ use std::fmt::{self, Debug, Display, Formatter};
 use atoi::atoi;
 use smallvec::alloc::borrow::Cow;
 
-use crate::error::DatabaseError;
+use crate::error::{DatabaseError, ErrorKind};
 use crate::postgres::message::{Notice, PgSeverity};
 
 /// An error returned from the PostgreSQL database.
@@ -199,7 +199,47 @@ impl DatabaseError for PgDatabaseError {
         .contains(&self.code())
     }
 
+    fn is_retryable(&self) -> bool {
+        matches!(
+            self.code(),
+            // serialization_failure
+            "40001"
+            // deadlock_detected
+            | "40P01"
+        )
+    }
+
     fn constraint(&self) -> Option<&str> {
         self.constraint()
     }
+
+    fn table(&self) -> Option<&str> {
+        self.table()
+    }
+
+    fn column(&self) -> Option<&str> {
+        self.column()
+    }
+
+    fn kind(&self) -> ErrorKind {
+        // https://www.postgresql.org/docs/current/errcodes-appendix.html
+        match self.code() {
+            // unique_violation
+            "23505" => ErrorKind::UniqueViolation,
+            // foreign_key_violation
+            "23503" => ErrorKind::ForeignKeyViolation,
+            // not_null_violation
+            "23502" => ErrorKind::NotNullViolation,
+            // check_violation
+            "23514" => ErrorKind::CheckViolation,
+            // deadlock_detected
+            "40P01" => ErrorKind::Deadlock,
+            // serialization_failure
+            "40001" => ErrorKind::SerializationFailure,
+            // query_canceled, raised by `statement_timeout` and cancel requests
+            // lock_not_available, raised by `lock_timeout`
+            "57014" | "55P03" => ErrorKind::Timeout,
+            _ => ErrorKind::Other,
+        }
+    }
 }
@@ -4,6 +4,7 @@ use crate::executor::Executor;
 
 mod advisory_lock;
 mod arguments;
+mod binary_copy;
 mod column;
 mod connection;
 mod copy;
@@ -13,7 +14,9 @@ mod io;
 mod listener;
 mod message;
 mod options;
+mod pipeline;
 mod query_result;
+mod replication;
 mod row;
 mod statement;
 mod transaction;
@@ -29,15 +32,20 @@ mod testing;
 
 pub use advisory_lock::{PgAdvisoryLock, PgAdvisoryLockGuard, PgAdvisoryLockKey};
 pub use arguments::{PgArgumentBuffer, PgArguments};
+pub use binary_copy::{PgBinaryCopyIn, PgCopyRecord, PgCopyRow, PgCopyRowEncoder};
 pub use column::PgColumn;
-pub use connection::PgConnection;
+pub use connection::{PgCancelToken, PgConnection};
 pub use copy::PgCopyIn;
 pub use database::Postgres;
 pub use error::{PgDatabaseError, PgErrorPosition};
 pub use listener::{PgListener, PgNotification};
 pub use message::PgSeverity;
-pub use options::{PgConnectOptions, PgSslMode};
+pub use options::{PgConnectOptions, PgSslMode, PgTargetSessionAttrs};
+pub use pipeline::{PgPipeline, PgPipelineOutput};
 pub use query_result::PgQueryResult;
+pub use replication::{
+    PgKeepalive, PgLsn, PgReplicationMessage, PgReplicationSlot, PgReplicationStream, PgXLogData,
+};
 pub use row::PgRow;
 pub use statement::PgStatement;
 pub use transaction::PgTransactionManager;
@@ -0,0 +1,69 @@
+use crate::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// The kind of server to accept when connecting to one of several hosts.
+///
+/// It is used by the [`target_session_attrs`](super::PgConnectOptions::target_session_attrs)
+/// method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PgTargetSessionAttrs {
+    /// Any server is acceptable.
+    Any,
+
+    /// The session must accept read-write transactions by default.
+    ReadWrite,
+
+    /// The session must not accept read-write transactions by default.
+    ReadOnly,
+
+    /// The server must not be in hot standby mode.
+    Primary,
+
+    /// The server must be in hot standby mode.
+    Standby,
+
+    /// First try to find a standby server, but if none of the hosts is one,
+    /// try again in [`Any`](Self::Any) mode.
+    PreferStandby,
+}
+
+impl Default for PgTargetSessionAttrs {
+    fn default() -> Self {
+        PgTargetSessionAttrs::Any
+    }
+}
+
+impl FromStr for PgTargetSessionAttrs {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match &*s.to_ascii_lowercase() {
+            "any" => PgTargetSessionAttrs::Any,
+            "read-write" => PgTargetSessionAttrs::ReadWrite,
+            "read-only" => PgTargetSessionAttrs::ReadOnly,
+            "primary" => PgTargetSessionAttrs::Primary,
+            "standby" => PgTargetSessionAttrs::Standby,
+            "prefer-standby" => PgTargetSessionAttrs::PreferStandby,
+
+            _ => {
+                return Err(Error::Configuration(
+                    format!("unknown value {:?} for `target_session_attrs`", s).into(),
+                ));
+            }
+        })
+    }
+}
+
+impl Display for PgTargetSessionAttrs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PgTargetSessionAttrs::Any => "any",
+            PgTargetSessionAttrs::ReadWrite => "read-write",
+            PgTargetSessionAttrs::ReadOnly => "read-only",
+            PgTargetSessionAttrs::Primary => "primary",
+            PgTargetSessionAttrs::Standby => "standby",
+            PgTargetSessionAttrs::PreferStandby => "prefer-standby",
+        })
+    }
+}
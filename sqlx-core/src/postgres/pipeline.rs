@@ -0,0 +1,336 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::error::Error;
+use crate::executor::Execute;
+use crate::logger::QueryLogger;
+use crate::postgres::message::{self, Bind, CommandComplete, DataRow, MessageFormat};
+use crate::postgres::statement::PgStatementMetadata;
+use crate::postgres::types::Oid;
+use crate::postgres::{PgArguments, PgConnection, PgRow, PgValueFormat, Postgres};
+
+impl PgConnection {
+    /// Start a pipeline of queries, which are sent to the server together and executed
+    /// in order, instead of waiting for the result of each query before sending the next.
+    ///
+    /// This saves a round trip per query, which makes a big difference for many small
+    /// queries such as bulk inserts.
+    ///
+    /// The queries are sent in chunks of a few kilobytes, and the results of each chunk are
+    /// read before the next one is sent, so a pipeline may hold any number of queries.
+    ///
+    /// Queries are executed with prepared statements, so each one must be a single statement.
+    /// Statements that were not prepared before are prepared (and cached) before the pipeline
+    /// is sent, which takes a round trip for each of them.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::error::Error;
+    /// # use sqlx_core::connection::Connection;
+    /// # use sqlx_core::postgres::PgConnection;
+    /// #
+    /// # fn main() {
+    /// # #[cfg(feature = "_rt-async-std")]
+    /// # sqlx_rt::async_std::task::block_on::<_, Result<(), Error>>(async move {
+    /// let mut conn = PgConnection::connect("postgres://localhost/mydb").await?;
+    ///
+    /// let mut pipeline = conn.pipeline();
+    ///
+    /// for id in 0..1000_i32 {
+    ///     pipeline.push(sqlx_core::query::query("INSERT INTO events (id) VALUES ($1)").bind(id));
+    /// }
+    ///
+    /// // either every insert succeeded, or none of them did
+    /// let outputs = pipeline.execute().await?;
+    /// assert_eq!(outputs.len(), 1000);
+    /// # Ok(())
+    /// # }).unwrap();
+    /// # }
+    /// ```
+    pub fn pipeline<'q>(&mut self) -> PgPipeline<'_, 'q> {
+        PgPipeline {
+            conn: self,
+            queries: Vec::new(),
+        }
+    }
+}
+
+/// A batch of queries to send to the server in one go, created by [`PgConnection::pipeline`].
+///
+/// The queries are executed when calling [`execute`](Self::execute) or
+/// [`execute_each`](Self::execute_each), which differ in what happens when one of the
+/// queries fails.
+pub struct PgPipeline<'c, 'q> {
+    conn: &'c mut PgConnection,
+    queries: Vec<PipelineQuery<'q>>,
+}
+
+struct PipelineQuery<'q> {
+    sql: &'q str,
+    arguments: PgArguments,
+    persistent: bool,
+    metadata: Option<Arc<PgStatementMetadata>>,
+}
+
+/// The output of one query in a [`PgPipeline`].
+#[derive(Debug, Default)]
+pub struct PgPipelineOutput {
+    rows: Vec<PgRow>,
+    rows_affected: u64,
+}
+
+impl PgPipelineOutput {
+    /// The rows returned by the query.
+    pub fn rows(&self) -> &[PgRow] {
+        &self.rows
+    }
+
+    /// Take the rows returned by the query.
+    pub fn into_rows(self) -> Vec<PgRow> {
+        self.rows
+    }
+
+    /// The number of rows affected by the query.
+    pub fn rows_affected(&self) -> u64 {
+        self.rows_affected
+    }
+}
+
+impl<'c, 'q> PgPipeline<'c, 'q> {
+    /// Add a query to the end of the pipeline.
+    pub fn push<E>(&mut self, mut query: E) -> &mut Self
+    where
+        E: Execute<'q, Postgres>,
+    {
+        self.queries.push(PipelineQuery {
+            sql: query.sql(),
+            metadata: query.statement().map(|s| Arc::clone(&s.metadata)),
+            arguments: query.take_arguments().unwrap_or_default(),
+            persistent: query.persistent(),
+        });
+
+        self
+    }
+
+    /// The number of queries in the pipeline.
+    pub fn len(&self) -> usize {
+        self.queries.len()
+    }
+
+    /// Returns `true` if there are no queries in the pipeline.
+    pub fn is_empty(&self) -> bool {
+        self.queries.is_empty()
+    }
+
+    /// Execute the queries as a single unit and return their outputs in order.
+    ///
+    /// Outside of a transaction, the queries run in one implicit transaction: if a query fails,
+    /// its error is returned, the queries after it are skipped, and none of them take effect.
+    /// Inside of a transaction, a failing query aborts the transaction.
+    pub async fn execute(self) -> Result<Vec<PgPipelineOutput>, Error> {
+        self.run(false).await?.into_iter().collect()
+    }
+
+    /// Execute the queries independently and return the result of each one in order.
+    ///
+    /// Outside of a transaction, each query commits on its own, so a failing query does not
+    /// affect the others. Inside of a transaction, the queries after a failing one fail
+    /// as well, because the transaction is aborted.
+    ///
+    /// The outer error is returned if the pipeline could not be executed at all,
+    /// for example because the connection was lost.
+    pub async fn execute_each(self) -> Result<Vec<Result<PgPipelineOutput, Error>>, Error> {
+        self.run(true).await
+    }
+
+    async fn run(self, sync_each: bool) -> Result<Vec<Result<PgPipelineOutput, Error>>, Error> {
+        let PgPipeline { conn, queries } = self;
+
+        if queries.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        conn.wait_until_ready().await?;
+
+        // caching more statements than the cache can hold would close the statements
+        // prepared first before they are executed
+        let distinct = queries.iter().map(|q| q.sql).collect::<HashSet<_>>().len();
+        let cache = distinct <= conn.cache_statement.capacity();
+
+        let mut prepared = Vec::with_capacity(queries.len());
+
+        for mut query in queries {
+            let (statement, metadata) = conn
+                .get_or_prepare(
+                    query.sql,
+                    &query.arguments.types,
+                    query.persistent && cache,
+                    query.metadata.take(),
+                )
+                .await?;
+
+            // patch holes created during encoding
+            query
+                .arguments
+                .apply_patches(conn, &metadata.parameters)
+                .await?;
+
+            prepared.push((query, statement, metadata));
+        }
+
+        // consume messages till `ReadyForQuery` before we start the pipeline
+        conn.wait_until_ready().await?;
+
+        conn.query_in_progress = true;
+
+        let mut outputs = Vec::with_capacity(prepared.len());
+        let result = send_and_recv(conn, &prepared, sync_each, &mut outputs).await;
+
+        // the pipeline is over, even if it failed
+        conn.query_in_progress = false;
+
+        result.map(|_| outputs)
+    }
+}
+
+// The queries are sent in chunks of about this many bytes, and the results of a chunk are
+// read before sending the next one. The server stops reading queries while it cannot send
+// their results, so writing all of a large pipeline first would block both sides once the
+// socket buffers are full.
+const CHUNK_BYTES: usize = 16 * 1024;
+
+async fn send_and_recv(
+    conn: &mut PgConnection,
+    prepared: &[(PipelineQuery<'_>, Oid, Arc<PgStatementMetadata>)],
+    sync_each: bool,
+    outputs: &mut Vec<Result<PgPipelineOutput, Error>>,
+) -> Result<(), Error> {
+    let mut start = 0;
+
+    while start < prepared.len() {
+        let mut end = start;
+
+        // a query larger than a chunk is sent on its own
+        while end < prepared.len() && (end == start || conn.stream.wbuf.len() < CHUNK_BYTES) {
+            let (query, statement, _) = &prepared[end];
+
+            conn.stream.write(Bind {
+                portal: None,
+                statement: *statement,
+                formats: &[PgValueFormat::Binary],
+                num_params: query.arguments.types.len() as i16,
+                params: &*query.arguments.buffer,
+                result_formats: &[PgValueFormat::Binary],
+            });
+
+            conn.stream.write(message::Execute {
+                portal: None,
+                limit: 0,
+            });
+
+            // each [Sync] ends an implicit transaction, and the server skips everything up to
+            // the next one after an error
+            if sync_each {
+                conn.write_sync();
+            }
+
+            end += 1;
+        }
+
+        let last = end == prepared.len();
+
+        if !sync_each {
+            if last {
+                conn.write_sync();
+            } else {
+                // the server holds back the results until it is asked for them
+                conn.stream.write(message::Flush);
+            }
+        }
+
+        conn.stream.flush().await?;
+
+        for (query, _, metadata) in &prepared[start..end] {
+            let mut logger = QueryLogger::new(query.sql, conn.log_settings.clone());
+
+            match recv_output(conn, metadata, &mut logger).await {
+                Ok(output) => outputs.push(Ok(output)),
+
+                Err(error @ Error::Database(_)) => {
+                    outputs.push(Err(error));
+
+                    if !sync_each {
+                        // the rest of the pipeline is skipped, up to the [Sync]
+                        if !last {
+                            conn.write_sync();
+                            conn.stream.flush().await?;
+                        }
+
+                        return conn.recv_ready_for_query().await;
+                    }
+                }
+
+                Err(error) => return Err(error),
+            }
+
+            if sync_each {
+                conn.recv_ready_for_query().await?;
+            }
+        }
+
+        start = end;
+    }
+
+    if !sync_each {
+        conn.recv_ready_for_query().await?;
+    }
+
+    Ok(())
+}
+
+async fn recv_output(
+    conn: &mut PgConnection,
+    metadata: &Arc<PgStatementMetadata>,
+    logger: &mut QueryLogger<'_>,
+) -> Result<PgPipelineOutput, Error> {
+    let mut output = PgPipelineOutput::default();
+
+    loop {
+        let message = conn.stream.recv().await?;
+
+        match message.format {
+            MessageFormat::BindComplete => {}
+
+            MessageFormat::DataRow => {
+                logger.increment_rows_returned();
+
+                let data: DataRow = message.decode()?;
+
+                output.rows.push(PgRow {
+                    data,
+                    format: PgValueFormat::Binary,
+                    metadata: Arc::clone(metadata),
+                });
+            }
+
+            MessageFormat::CommandComplete => {
+                let cc: CommandComplete = message.decode()?;
+
+                output.rows_affected = cc.rows_affected();
+                logger.increase_rows_affected(output.rows_affected);
+
+                return Ok(output);
+            }
+
+            MessageFormat::EmptyQueryResponse => return Ok(output),
+
+            _ => {
+                return Err(err_protocol!(
+                    "pipeline: unexpected message: {:?}",
+                    message.format
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,525 @@
+use std::fmt::{self, Display, Formatter, Write};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bytes::{Buf, BufMut, Bytes};
+
+use crate::error::{Error, Result};
+use crate::executor::Executor;
+use crate::postgres::connection::PgConnection;
+use crate::postgres::message::{CopyData, CopyDone, CopyResponse, MessageFormat, Query};
+use crate::row::Row;
+
+// the epoch of PostgreSQL timestamps, 2000-01-01 00:00:00 UTC
+const PG_EPOCH: Duration = Duration::from_secs(946_684_800);
+
+/// A position in the write-ahead log (`pg_lsn`), written as two hexadecimal numbers
+/// separated by a slash, such as `16/B374D848`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PgLsn(pub u64);
+
+impl Display for PgLsn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:X}/{:X}", self.0 >> 32, self.0 as u32)
+    }
+}
+
+impl FromStr for PgLsn {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parse = |part: &str| u32::from_str_radix(part, 16).ok();
+
+        s.split_once('/')
+            .and_then(|(hi, lo)| Some((u64::from(parse(hi)?) << 32) | u64::from(parse(lo)?)))
+            .map(PgLsn)
+            .ok_or_else(|| Error::Decode(format!("invalid LSN {:?}", s).into()))
+    }
+}
+
+/// A logical replication slot created by [`PgConnection::create_replication_slot`].
+#[derive(Debug, Clone)]
+pub struct PgReplicationSlot {
+    name: String,
+    consistent_point: PgLsn,
+    snapshot_name: Option<String>,
+}
+
+impl PgReplicationSlot {
+    /// The name of the slot.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The position from which the slot streams changes; every transaction that commits
+    /// after it is decoded.
+    pub fn consistent_point(&self) -> PgLsn {
+        self.consistent_point
+    }
+
+    /// The name of the snapshot exported when the slot was created, which can be imported by
+    /// another connection with `SET TRANSACTION SNAPSHOT` to copy the data as of the
+    /// consistent point. It is only valid until the next command on this connection.
+    pub fn snapshot_name(&self) -> Option<&str> {
+        self.snapshot_name.as_deref()
+    }
+}
+
+impl PgConnection {
+    /// Create a logical replication slot that decodes changes with the given output plugin,
+    /// such as `pgoutput`, `wal2json` or `test_decoding`.
+    ///
+    /// A temporary slot is dropped when this connection is closed.
+    ///
+    /// The connection must have been opened with [`PgConnectOptions::replication`].
+    ///
+    /// [`PgConnectOptions::replication`]: crate::postgres::PgConnectOptions::replication()
+    pub async fn create_replication_slot(
+        &mut self,
+        slot_name: &str,
+        output_plugin: &str,
+        temporary: bool,
+    ) -> Result<PgReplicationSlot> {
+        let query = format!(
+            "CREATE_REPLICATION_SLOT {}{} LOGICAL {}",
+            quote_identifier(slot_name),
+            if temporary { " TEMPORARY" } else { "" },
+            quote_identifier(output_plugin)
+        );
+
+        // replication commands are only accepted with the simple query protocol
+        let row = self.fetch_one(&*query).await?;
+
+        Ok(PgReplicationSlot {
+            name: row.try_get("slot_name")?,
+            consistent_point: row.try_get::<&str, _>("consistent_point")?.parse()?,
+            snapshot_name: row.try_get("snapshot_name")?,
+        })
+    }
+
+    /// Drop a replication slot, freeing the write-ahead log it retains.
+    ///
+    /// If the slot is in use by another connection, an error is returned unless `wait` is
+    /// `true`, in which case this waits until the slot is released.
+    pub async fn drop_replication_slot(&mut self, slot_name: &str, wait: bool) -> Result<()> {
+        let query = format!(
+            "DROP_REPLICATION_SLOT {}{}",
+            quote_identifier(slot_name),
+            if wait { " WAIT" } else { "" }
+        );
+
+        self.execute(&*query).await?;
+
+        Ok(())
+    }
+
+    /// Start streaming the changes of a logical replication slot, from `start_lsn` or from the
+    /// last position acknowledged on the slot if it is later. Passing `PgLsn(0)` resumes from
+    /// the last acknowledged position.
+    ///
+    /// The `options` are passed to the output plugin of the slot, for example
+    /// `[("proto_version", "1"), ("publication_names", "my_publication")]` for `pgoutput`.
+    ///
+    /// The changes are only removed from the slot once they have been acknowledged with
+    /// [`PgReplicationStream::acknowledge`]. Unacknowledged changes are streamed again the
+    /// next time replication is started.
+    ///
+    /// The connection must have been opened with [`PgConnectOptions::replication`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> sqlx_core::error::Result<()> {
+    /// use sqlx_core::connection::ConnectOptions;
+    /// use sqlx_core::postgres::{PgConnectOptions, PgLsn, PgReplicationMessage};
+    ///
+    /// let mut conn = "postgres://localhost/mydb?replication=database"
+    ///     .parse::<PgConnectOptions>()?
+    ///     .connect()
+    ///     .await?;
+    ///
+    /// let mut stream = conn
+    ///     .start_replication("my_slot", PgLsn(0), &[("include-xids", "0")])
+    ///     .await?;
+    ///
+    /// while let Some(message) = stream.recv().await? {
+    ///     match message {
+    ///         PgReplicationMessage::XLogData(data) => {
+    ///             println!("{}", String::from_utf8_lossy(data.data()));
+    ///
+    ///             stream.acknowledge(data.wal_start()).await?;
+    ///         }
+    ///
+    ///         PgReplicationMessage::Keepalive(keepalive) if keepalive.reply_requested() => {
+    ///             stream.send_status_update().await?;
+    ///         }
+    ///
+    ///         PgReplicationMessage::Keepalive(_) => {}
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`PgConnectOptions::replication`]: crate::postgres::PgConnectOptions::replication()
+    pub async fn start_replication(
+        &mut self,
+        slot_name: &str,
+        start_lsn: PgLsn,
+        options: &[(&str, &str)],
+    ) -> Result<PgReplicationStream<'_>> {
+        let mut query = format!(
+            "START_REPLICATION SLOT {} LOGICAL {}",
+            quote_identifier(slot_name),
+            start_lsn
+        );
+
+        for (i, (name, value)) in options.iter().enumerate() {
+            let separator = if i == 0 { " (" } else { ", " };
+
+            write!(
+                query,
+                "{}{} {}",
+                separator,
+                quote_identifier(name),
+                quote_literal(value)
+            )
+            .expect("failed to write an option to the query");
+        }
+
+        if !options.is_empty() {
+            query.push(')');
+        }
+
+        self.wait_until_ready().await?;
+        self.stream.send(Query(&query)).await?;
+
+        // the server is ready for queries again once the stream ends
+        self.pending_ready_for_query_count += 1;
+
+        let _: CopyResponse = self
+            .stream
+            .recv_expect(MessageFormat::CopyBothResponse)
+            .await?;
+
+        Ok(PgReplicationStream {
+            conn: self,
+            acknowledged: start_lsn,
+            done: false,
+        })
+    }
+}
+
+/// A stream of changes from a logical replication slot, started by
+/// [`PgConnection::start_replication`].
+///
+/// The server expects a status update at least every `wal_sender_timeout` (60 seconds by
+/// default), and closes the connection otherwise. Keepalive messages that request a reply
+/// must be answered with [`send_status_update`](Self::send_status_update) or
+/// [`acknowledge`](Self::acknowledge).
+///
+/// ### Note
+/// [`finish`](Self::finish) should be called to stop streaming. If the stream is dropped
+/// instead, the next use of the connection has to discard the changes that are still in
+/// flight.
+pub struct PgReplicationStream<'c> {
+    conn: &'c mut PgConnection,
+
+    // the last position reported in a status update
+    acknowledged: PgLsn,
+
+    // the stream has ended and the connection is ready for queries again
+    done: bool,
+}
+
+impl PgReplicationStream<'_> {
+    /// Receive the next message from the server.
+    ///
+    /// Returns `None` once the server has ended the stream.
+    pub async fn recv(&mut self) -> Result<Option<PgReplicationMessage>> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let message = match self.conn.stream.recv().await {
+            Ok(message) => message,
+
+            Err(error) => {
+                // the server left replication mode
+                self.done = true;
+
+                return Err(error);
+            }
+        };
+
+        match message.format {
+            MessageFormat::CopyData => {
+                PgReplicationMessage::decode(message.decode::<CopyData<Bytes>>()?.0).map(Some)
+            }
+
+            MessageFormat::CopyDone => {
+                self.done = true;
+
+                // both sides must end the stream
+                self.conn.stream.send(CopyDone).await?;
+                self.conn.wait_until_ready().await?;
+
+                Ok(None)
+            }
+
+            format => Err(err_protocol!(
+                "unexpected message format during replication: {:?}",
+                format
+            )),
+        }
+    }
+
+    /// Report that every change up to `lsn` has been processed, so the server can remove it
+    /// from the slot.
+    ///
+    /// The position should be the [`wal_start`](PgXLogData::wal_start) of the last message
+    /// that was fully processed, such as the commit of a transaction.
+    pub async fn acknowledge(&mut self, lsn: PgLsn) -> Result<()> {
+        self.acknowledged = lsn;
+        self.send_status_update().await
+    }
+
+    /// Send a status update with the last acknowledged position, to keep the connection alive.
+    pub async fn send_status_update(&mut self) -> Result<()> {
+        if self.done {
+            // status updates are only accepted while streaming
+            return Ok(());
+        }
+
+        let lsn = self.acknowledged.0;
+        let mut buf = Vec::with_capacity(34);
+
+        buf.push(b'r');
+
+        // the positions that were written to disk, flushed to disk and applied
+        buf.put_u64(lsn);
+        buf.put_u64(lsn);
+        buf.put_u64(lsn);
+
+        buf.put_i64(to_pg_time(SystemTime::now()));
+
+        // do not request a reply
+        buf.push(0);
+
+        self.conn.stream.send(CopyData(buf)).await
+    }
+
+    /// Stop streaming, and wait for the connection to be ready for queries again.
+    ///
+    /// Changes that are still in flight are discarded, and streamed again the next time
+    /// replication is started unless they were acknowledged.
+    pub async fn finish(mut self) -> Result<()> {
+        if !self.done {
+            self.done = true;
+
+            self.conn.stream.send(CopyDone).await?;
+            self.conn.wait_until_ready().await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for PgReplicationStream<'_> {
+    fn drop(&mut self) {
+        if !self.done {
+            // the remaining messages are discarded the next time the connection is used
+            self.conn.stream.write(CopyDone);
+        }
+    }
+}
+
+/// A message received from a [`PgReplicationStream`].
+#[derive(Debug)]
+pub enum PgReplicationMessage {
+    /// Changes decoded by the output plugin of the slot.
+    XLogData(PgXLogData),
+
+    /// A keepalive, which may request a status update.
+    Keepalive(PgKeepalive),
+}
+
+impl PgReplicationMessage {
+    fn decode(mut buf: Bytes) -> Result<Self> {
+        if buf.is_empty() {
+            return Err(err_protocol!("empty replication message"));
+        }
+
+        match buf.get_u8() {
+            b'w' if buf.len() >= 24 => Ok(PgReplicationMessage::XLogData(PgXLogData {
+                wal_start: PgLsn(buf.get_u64()),
+                wal_end: PgLsn(buf.get_u64()),
+                server_time: from_pg_time(buf.get_i64()),
+                data: buf,
+            })),
+
+            b'k' if buf.len() >= 17 => Ok(PgReplicationMessage::Keepalive(PgKeepalive {
+                wal_end: PgLsn(buf.get_u64()),
+                server_time: from_pg_time(buf.get_i64()),
+                reply_requested: buf.get_u8() != 0,
+            })),
+
+            ty => Err(err_protocol!(
+                "unexpected replication message: {:?} ({} bytes)",
+                ty as char,
+                buf.len()
+            )),
+        }
+    }
+}
+
+/// Changes decoded by the output plugin of a replication slot.
+#[derive(Debug)]
+pub struct PgXLogData {
+    wal_start: PgLsn,
+    wal_end: PgLsn,
+    server_time: SystemTime,
+    data: Bytes,
+}
+
+impl PgXLogData {
+    /// The position of the changes in the write-ahead log.
+    pub fn wal_start(&self) -> PgLsn {
+        self.wal_start
+    }
+
+    /// The current end of the write-ahead log on the server, which may be past the changes
+    /// that were streamed so far.
+    pub fn wal_end(&self) -> PgLsn {
+        self.wal_end
+    }
+
+    /// The time at which the server sent the message.
+    pub fn server_time(&self) -> SystemTime {
+        self.server_time
+    }
+
+    /// The changes, in the format of the output plugin.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Take the changes, in the format of the output plugin.
+    pub fn into_data(self) -> Bytes {
+        self.data
+    }
+}
+
+/// A keepalive sent by the server while streaming changes.
+#[derive(Debug)]
+pub struct PgKeepalive {
+    wal_end: PgLsn,
+    server_time: SystemTime,
+    reply_requested: bool,
+}
+
+impl PgKeepalive {
+    /// The current end of the write-ahead log on the server.
+    pub fn wal_end(&self) -> PgLsn {
+        self.wal_end
+    }
+
+    /// The time at which the server sent the message.
+    pub fn server_time(&self) -> SystemTime {
+        self.server_time
+    }
+
+    /// Whether the server asks for a status update right away, to avoid a timeout.
+    pub fn reply_requested(&self) -> bool {
+        self.reply_requested
+    }
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+// PostgreSQL timestamps are microseconds since its epoch
+fn from_pg_time(micros: i64) -> SystemTime {
+    let epoch = UNIX_EPOCH + PG_EPOCH;
+
+    if micros >= 0 {
+        epoch + Duration::from_micros(micros as u64)
+    } else {
+        epoch - Duration::from_micros(micros.unsigned_abs())
+    }
+}
+
+fn to_pg_time(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH + PG_EPOCH) {
+        Ok(since) => since.as_micros() as i64,
+        Err(before) => -(before.duration().as_micros() as i64),
+    }
+}
+
+#[test]
+fn test_lsn() {
+    assert_eq!(
+        "16/B374D848".parse::<PgLsn>().unwrap(),
+        PgLsn(0x16_B374_D848)
+    );
+    assert_eq!(PgLsn(0x16_B374_D848).to_string(), "16/B374D848");
+    assert_eq!(PgLsn(0).to_string(), "0/0");
+
+    assert!("16B374D848".parse::<PgLsn>().is_err());
+    assert!("16/B374D848F".parse::<PgLsn>().is_err());
+}
+
+#[test]
+fn test_decode_replication_message() {
+    let mut buf = vec![b'w'];
+    buf.put_u64(0x10);
+    buf.put_u64(0x20);
+    buf.put_i64(1_000_000);
+    buf.extend_from_slice(b"BEGIN 42");
+
+    match PgReplicationMessage::decode(buf.into()).unwrap() {
+        PgReplicationMessage::XLogData(data) => {
+            assert_eq!(data.wal_start(), PgLsn(0x10));
+            assert_eq!(data.wal_end(), PgLsn(0x20));
+            assert_eq!(
+                data.server_time(),
+                UNIX_EPOCH + PG_EPOCH + Duration::from_secs(1)
+            );
+            assert_eq!(data.data(), b"BEGIN 42");
+        }
+
+        message => panic!("unexpected message: {:?}", message),
+    }
+
+    let mut buf = vec![b'k'];
+    buf.put_u64(0x20);
+    buf.put_i64(-1_000_000);
+    buf.push(1);
+
+    match PgReplicationMessage::decode(buf.into()).unwrap() {
+        PgReplicationMessage::Keepalive(keepalive) => {
+            assert_eq!(keepalive.wal_end(), PgLsn(0x20));
+            assert_eq!(
+                keepalive.server_time(),
+                UNIX_EPOCH + PG_EPOCH - Duration::from_secs(1)
+            );
+            assert!(keepalive.reply_requested());
+        }
+
+        message => panic!("unexpected message: {:?}", message),
+    }
+
+    assert!(PgReplicationMessage::decode(Bytes::from_static(b"k\0")).is_err());
+}
+
+#[test]
+fn test_pg_time() {
+    let time = UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+
+    assert_eq!(from_pg_time(to_pg_time(time)), time);
+}
@@ -8,6 +8,7 @@ use crate::row::Row;
 use std::sync::Arc;
 
 /// Implementation of [`Row`] for PostgreSQL.
+#[derive(Debug)]
 pub struct PgRow {
     pub(crate) data: DataRow,
     pub(crate) format: PgValueFormat,
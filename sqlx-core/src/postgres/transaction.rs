@@ -4,8 +4,8 @@ use crate::error::Error;
 use crate::executor::Executor;
 use crate::postgres::{PgConnection, Postgres};
 use crate::transaction::{
-    begin_ansi_transaction_sql, commit_ansi_transaction_sql, rollback_ansi_transaction_sql,
-    TransactionManager,
+    begin_ansi_transaction_sql, check_savepoint_options, commit_ansi_transaction_sql,
+    rollback_ansi_transaction_sql, IsolationLevel, TransactionManager, TransactionOptions,
 };
 
 /// Implementation of [`TransactionManager`] for PostgreSQL.
@@ -15,9 +15,22 @@ impl TransactionManager for PgTransactionManager {
     type Database = Postgres;
 
     fn begin(conn: &mut PgConnection) -> BoxFuture<'_, Result<(), Error>> {
+        Self::begin_with(conn, TransactionOptions::default())
+    }
+
+    fn begin_with(
+        conn: &mut PgConnection,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
-            conn.execute(&*begin_ansi_transaction_sql(conn.transaction_depth))
-                .await?;
+            check_savepoint_options(conn.transaction_depth, &options)?;
+
+            if conn.transaction_depth == 0 {
+                conn.execute(&*begin_transaction_sql(&options)).await?;
+            } else {
+                conn.execute(&*begin_ansi_transaction_sql(conn.transaction_depth))
+                    .await?;
+            }
 
             conn.transaction_depth += 1;
 
@@ -25,6 +38,41 @@ impl TransactionManager for PgTransactionManager {
         })
     }
 
+    fn savepoint<'c>(
+        conn: &'c mut PgConnection,
+        name: &'c str,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        Box::pin(async move {
+            conn.execute(&*format!("SAVEPOINT {}", name)).await?;
+
+            Ok(())
+        })
+    }
+
+    fn rollback_to_savepoint<'c>(
+        conn: &'c mut PgConnection,
+        name: &'c str,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        Box::pin(async move {
+            conn.execute(&*format!("ROLLBACK TO SAVEPOINT {}", name))
+                .await?;
+
+            Ok(())
+        })
+    }
+
+    fn release_savepoint<'c>(
+        conn: &'c mut PgConnection,
+        name: &'c str,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        Box::pin(async move {
+            conn.execute(&*format!("RELEASE SAVEPOINT {}", name))
+                .await?;
+
+            Ok(())
+        })
+    }
+
     fn commit(conn: &mut PgConnection) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
             if conn.transaction_depth > 0 {
@@ -59,3 +107,52 @@ impl TransactionManager for PgTransactionManager {
         }
     }
 }
+
+fn begin_transaction_sql(options: &TransactionOptions) -> String {
+    let mut modes = Vec::new();
+
+    if let Some(level) = options.isolation_level {
+        modes.push(match level {
+            // snapshot isolation is how PostgreSQL implements REPEATABLE READ
+            IsolationLevel::Snapshot => "ISOLATION LEVEL REPEATABLE READ",
+            IsolationLevel::ReadUncommitted => "ISOLATION LEVEL READ UNCOMMITTED",
+            IsolationLevel::ReadCommitted => "ISOLATION LEVEL READ COMMITTED",
+            IsolationLevel::RepeatableRead => "ISOLATION LEVEL REPEATABLE READ",
+            IsolationLevel::Serializable => "ISOLATION LEVEL SERIALIZABLE",
+        });
+    }
+
+    if options.read_only {
+        modes.push("READ ONLY");
+    }
+
+    if options.deferrable {
+        modes.push("DEFERRABLE");
+    }
+
+    if modes.is_empty() {
+        "BEGIN".to_owned()
+    } else {
+        format!("BEGIN {}", modes.join(", "))
+    }
+}
+
+#[test]
+fn test_begin_transaction_sql() {
+    assert_eq!(begin_transaction_sql(&TransactionOptions::new()), "BEGIN");
+
+    assert_eq!(
+        begin_transaction_sql(
+            &TransactionOptions::new()
+                .isolation_level(IsolationLevel::Serializable)
+                .read_only(true)
+                .deferrable(true)
+        ),
+        "BEGIN ISOLATION LEVEL SERIALIZABLE, READ ONLY, DEFERRABLE"
+    );
+
+    assert_eq!(
+        begin_transaction_sql(&TransactionOptions::new().isolation_level(IsolationLevel::Snapshot)),
+        "BEGIN ISOLATION LEVEL REPEATABLE READ"
+    );
+}
@@ -15,9 +15,11 @@ use crate::sqlite::connection::describe::describe;
 use crate::sqlite::connection::establish::EstablishParams;
 use crate::sqlite::connection::ConnectionState;
 use crate::sqlite::connection::{execute, ConnectionHandleRaw};
+use crate::sqlite::transaction::begin_transaction_sql;
 use crate::sqlite::{Sqlite, SqliteArguments, SqliteQueryResult, SqliteRow, SqliteStatement};
 use crate::transaction::{
-    begin_ansi_transaction_sql, commit_ansi_transaction_sql, rollback_ansi_transaction_sql,
+    check_savepoint_options, commit_ansi_transaction_sql, rollback_ansi_transaction_sql,
+    TransactionOptions,
 };
 
 // Each SQLite connection has a dedicated thread.
@@ -55,6 +57,7 @@ enum Command {
         tx: flume::Sender<Result<Either<SqliteQueryResult, SqliteRow>, Error>>,
     },
     Begin {
+        options: TransactionOptions,
         tx: rendezvous_oneshot::Sender<Result<(), Error>>,
     },
     Commit {
@@ -159,14 +162,15 @@ impl ConnectionWorker {
 
                             update_cached_statements_size(&conn, &shared.cached_statements_size);
                         }
-                        Command::Begin { tx } => {
+                        Command::Begin { options, tx } => {
                             let depth = conn.transaction_depth;
-                            let res =
+                            let res = check_savepoint_options(depth, &options).and_then(|_| {
                                 conn.handle
-                                    .exec(begin_ansi_transaction_sql(depth))
+                                    .exec(begin_transaction_sql(depth, &options))
                                     .map(|_| {
                                         conn.transaction_depth += 1;
-                                    });
+                                    })
+                            });
                             let res_ok = res.is_ok();
 
                             if tx.blocking_send(res).is_err() && res_ok {
@@ -310,8 +314,8 @@ impl ConnectionWorker {
         Ok(rx)
     }
 
-    pub(crate) async fn begin(&mut self) -> Result<(), Error> {
-        self.oneshot_cmd_with_ack(|tx| Command::Begin { tx })
+    pub(crate) async fn begin(&mut self, options: TransactionOptions) -> Result<(), Error> {
+        self.oneshot_cmd_with_ack(|tx| Command::Begin { options, tx })
             .await?
     }
 
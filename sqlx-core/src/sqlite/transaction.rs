@@ -1,8 +1,13 @@
+use std::borrow::Cow;
+
 use futures_core::future::BoxFuture;
 
 use crate::error::Error;
+use crate::executor::Executor;
 use crate::sqlite::{Sqlite, SqliteConnection};
-use crate::transaction::TransactionManager;
+use crate::transaction::{
+    begin_ansi_transaction_sql, IsolationLevel, TransactionManager, TransactionOptions,
+};
 
 /// Implementation of [`TransactionManager`] for SQLite.
 pub struct SqliteTransactionManager;
@@ -11,7 +16,49 @@ impl TransactionManager for SqliteTransactionManager {
     type Database = Sqlite;
 
     fn begin(conn: &mut SqliteConnection) -> BoxFuture<'_, Result<(), Error>> {
-        Box::pin(conn.worker.begin())
+        Box::pin(conn.worker.begin(TransactionOptions::default()))
+    }
+
+    fn begin_with(
+        conn: &mut SqliteConnection,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(conn.worker.begin(options))
+    }
+
+    fn savepoint<'c>(
+        conn: &'c mut SqliteConnection,
+        name: &'c str,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        Box::pin(async move {
+            conn.execute(&*format!("SAVEPOINT {}", name)).await?;
+
+            Ok(())
+        })
+    }
+
+    fn rollback_to_savepoint<'c>(
+        conn: &'c mut SqliteConnection,
+        name: &'c str,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        Box::pin(async move {
+            conn.execute(&*format!("ROLLBACK TO SAVEPOINT {}", name))
+                .await?;
+
+            Ok(())
+        })
+    }
+
+    fn release_savepoint<'c>(
+        conn: &'c mut SqliteConnection,
+        name: &'c str,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        Box::pin(async move {
+            conn.execute(&*format!("RELEASE SAVEPOINT {}", name))
+                .await?;
+
+            Ok(())
+        })
     }
 
     fn commit(conn: &mut SqliteConnection) -> BoxFuture<'_, Result<(), Error>> {
@@ -26,3 +73,71 @@ impl TransactionManager for SqliteTransactionManager {
         conn.worker.start_rollback().ok();
     }
 }
+
+// SQLite transactions are always serializable, the isolation level decides when the
+// locks are taken
+pub(crate) fn begin_transaction_sql(
+    depth: usize,
+    options: &TransactionOptions,
+) -> Cow<'static, str> {
+    // a read-only transaction never blocks the writers, as `BEGIN` is deferred
+    if depth > 0 || options.read_only {
+        return begin_ansi_transaction_sql(depth);
+    }
+
+    match options.isolation_level {
+        None => begin_ansi_transaction_sql(depth),
+
+        // no other connection may read or write until the transaction ends
+        Some(IsolationLevel::Serializable) => Cow::Borrowed("BEGIN EXCLUSIVE"),
+
+        // no other connection may write until the transaction ends
+        Some(IsolationLevel::RepeatableRead) | Some(IsolationLevel::Snapshot) => {
+            Cow::Borrowed("BEGIN IMMEDIATE")
+        }
+
+        // the locks are taken by the statements that need them
+        Some(IsolationLevel::ReadUncommitted) | Some(IsolationLevel::ReadCommitted) => {
+            Cow::Borrowed("BEGIN DEFERRED")
+        }
+    }
+}
+
+#[test]
+fn test_begin_transaction_sql() {
+    assert_eq!(
+        begin_transaction_sql(0, &TransactionOptions::new()),
+        "BEGIN"
+    );
+
+    assert_eq!(
+        begin_transaction_sql(
+            0,
+            &TransactionOptions::new().isolation_level(IsolationLevel::Serializable)
+        ),
+        "BEGIN EXCLUSIVE"
+    );
+
+    assert_eq!(
+        begin_transaction_sql(
+            0,
+            &TransactionOptions::new().isolation_level(IsolationLevel::RepeatableRead)
+        ),
+        "BEGIN IMMEDIATE"
+    );
+
+    assert_eq!(
+        begin_transaction_sql(
+            0,
+            &TransactionOptions::new()
+                .isolation_level(IsolationLevel::Serializable)
+                .read_only(true)
+        ),
+        "BEGIN"
+    );
+
+    assert_eq!(
+        begin_transaction_sql(1, &TransactionOptions::new()),
+        "SAVEPOINT _sqlx_savepoint_1"
+    );
+}
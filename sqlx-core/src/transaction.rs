@@ -1,6 +1,8 @@
 use std::borrow::Cow;
+use std::cmp;
 use std::fmt::{self, Debug, Formatter};
 use std::ops::{Deref, DerefMut};
+use std::time::Duration;
 
 use futures_core::future::BoxFuture;
 
@@ -20,6 +22,47 @@ pub trait TransactionManager {
         conn: &mut <Self::Database as Database>::Connection,
     ) -> BoxFuture<'_, Result<(), Error>>;
 
+    /// Begin a new transaction with the given options.
+    ///
+    /// Options other than the defaults cannot be applied to a savepoint within the active
+    /// transaction and return an error.
+    ///
+    /// The default implementation only supports the default options.
+    fn begin_with(
+        conn: &mut <Self::Database as Database>::Connection,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        if options.is_default() {
+            Self::begin(conn)
+        } else {
+            Box::pin(async move { Err(unsupported("transaction options")) })
+        }
+    }
+
+    /// Establish a named savepoint within the active transaction.
+    fn savepoint<'c>(
+        _conn: &'c mut <Self::Database as Database>::Connection,
+        _name: &'c str,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        Box::pin(async move { Err(unsupported("named savepoints")) })
+    }
+
+    /// Restore the active transaction to a named savepoint, which is kept.
+    fn rollback_to_savepoint<'c>(
+        _conn: &'c mut <Self::Database as Database>::Connection,
+        _name: &'c str,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        Box::pin(async move { Err(unsupported("named savepoints")) })
+    }
+
+    /// Release a named savepoint, keeping the changes made since it was established.
+    fn release_savepoint<'c>(
+        _conn: &'c mut <Self::Database as Database>::Connection,
+        _name: &'c str,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        Box::pin(async move { Err(unsupported("named savepoints")) })
+    }
+
     /// Commit the active transaction or release the most recent savepoint.
     fn commit(
         conn: &mut <Self::Database as Database>::Connection,
@@ -34,6 +77,194 @@ pub trait TransactionManager {
     fn start_rollback(conn: &mut <Self::Database as Database>::Connection);
 }
 
+/// The isolation level of a transaction, see [`TransactionOptions::isolation_level`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IsolationLevel {
+    /// Statements may read rows written by other transactions that have not been committed
+    /// yet.
+    ReadUncommitted,
+
+    /// Statements only read rows that have been committed before they started.
+    ReadCommitted,
+
+    /// Rows read by the transaction cannot change until it completes.
+    RepeatableRead,
+
+    /// The transaction behaves as if no other transaction ran at the same time.
+    Serializable,
+
+    /// The transaction reads from a snapshot of the database taken when it started.
+    ///
+    /// This is `SNAPSHOT` in MSSQL, which must be allowed by the database, and
+    /// `REPEATABLE READ` in PostgreSQL and MySQL, which both implement it with a snapshot.
+    Snapshot,
+}
+
+// the name of the isolation level in `SET TRANSACTION ISOLATION LEVEL`
+impl fmt::Display for IsolationLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            IsolationLevel::ReadUncommitted => "READ UNCOMMITTED",
+            IsolationLevel::ReadCommitted => "READ COMMITTED",
+            IsolationLevel::RepeatableRead => "REPEATABLE READ",
+            IsolationLevel::Serializable => "SERIALIZABLE",
+            IsolationLevel::Snapshot => "SNAPSHOT",
+        })
+    }
+}
+
+/// Options for a transaction started with [`Connection::begin_with`] or [`Pool::begin_with`].
+///
+/// Every driver renders the options with its own syntax:
+///
+/// |Option|PostgreSQL|MySQL|MSSQL|SQLite|
+/// |------|----------|-----|-----|------|
+/// | [`isolation_level`](Self::isolation_level) | `BEGIN ISOLATION LEVEL ..` | `SET TRANSACTION ISOLATION LEVEL ..` | `SET TRANSACTION ISOLATION LEVEL ..` | `Serializable` is `BEGIN EXCLUSIVE`, `RepeatableRead` and `Snapshot` are `BEGIN IMMEDIATE`, others are `BEGIN DEFERRED` |
+/// | [`read_only`](Self::read_only) | `READ ONLY` | `START TRANSACTION READ ONLY` | ignored | `BEGIN DEFERRED`, whatever the isolation level |
+/// | [`deferrable`](Self::deferrable) | `DEFERRABLE` | ignored | ignored | ignored |
+///
+/// SQLite transactions are always serializable; the isolation level only decides whether
+/// the locks are taken when the transaction starts, instead of by its first statements.
+///
+/// PostgreSQL and MySQL have no separate `SNAPSHOT` level: [`IsolationLevel::Snapshot`]
+/// starts a `REPEATABLE READ` transaction, which both of them implement with a snapshot.
+/// MySQL also takes the snapshot right away with `START TRANSACTION WITH CONSISTENT SNAPSHOT`.
+///
+/// As the isolation level of an MSSQL session outlives the transaction, it is set back to
+/// `READ COMMITTED` when the transaction ends.
+///
+/// The default options start the same transaction as [`Connection::begin`].
+///
+/// ```rust,no_run
+/// # async fn example(pool: sqlx_core::postgres::PgPool) -> Result<(), sqlx_core::error::Error> {
+/// use sqlx_core::transaction::{IsolationLevel, TransactionOptions};
+///
+/// let mut tx = pool
+///     .begin_with(
+///         TransactionOptions::new()
+///             .isolation_level(IsolationLevel::Serializable)
+///             .read_only(true),
+///     )
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Connection::begin_with`]: crate::connection::Connection::begin_with()
+/// [`Connection::begin`]: crate::connection::Connection::begin()
+/// [`Pool::begin_with`]: crate::pool::Pool::begin_with()
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TransactionOptions {
+    pub(crate) isolation_level: Option<IsolationLevel>,
+    pub(crate) read_only: bool,
+    pub(crate) deferrable: bool,
+}
+
+impl TransactionOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the isolation level of the transaction.
+    ///
+    /// Defaults to the isolation level of the session, which is usually `ReadCommitted`
+    /// (`RepeatableRead` in MySQL).
+    pub fn isolation_level(mut self, isolation_level: IsolationLevel) -> Self {
+        self.isolation_level = Some(isolation_level);
+        self
+    }
+
+    /// Sets whether the transaction may only read from the database.
+    ///
+    /// Defaults to `false`.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Sets whether a serializable, read-only transaction may wait to start until it can
+    /// run without the risk of a serialization failure (PostgreSQL only).
+    ///
+    /// Defaults to `false`.
+    pub fn deferrable(mut self, deferrable: bool) -> Self {
+        self.deferrable = deferrable;
+        self
+    }
+
+    pub(crate) fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// How [`Connection::transaction_with_retry`] retries a transaction that failed because of a
+/// conflict with a concurrent transaction.
+///
+/// The delay before each retry starts at [`initial_backoff`](Self::initial_backoff) and
+/// doubles with every attempt, up to [`max_backoff`](Self::max_backoff).
+///
+/// [`Connection::transaction_with_retry`]: crate::connection::Connection::transaction_with_retry()
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RetryPolicy {
+    pub(crate) max_attempts: u32,
+    pub(crate) initial_backoff: Duration,
+    pub(crate) max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how many times the transaction is run before its error is returned, including the
+    /// first attempt.
+    ///
+    /// Defaults to `5`.
+    ///
+    /// # Panics
+    /// If `max_attempts` is `0`.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        assert!(max_attempts > 0, "max_attempts must be at least 1");
+
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Sets the delay before the first retry.
+    ///
+    /// Defaults to 10 milliseconds.
+    pub fn initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Sets the longest delay between two attempts.
+    ///
+    /// Defaults to 1 second.
+    pub fn max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    // the delay before the given retry, starting at 1
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .checked_mul(1 << cmp::min(retry - 1, 31))
+            .map_or(self.max_backoff, |backoff| {
+                cmp::min(backoff, self.max_backoff)
+            })
+    }
+}
+
 /// An in-progress database transaction or savepoint.
 ///
 /// A transaction starts with a call to [`Pool::begin`] or [`Connection::begin`].
@@ -44,12 +275,15 @@ pub trait TransactionManager {
 ///
 /// A savepoint is a special mark inside a transaction that allows all commands that are
 /// executed after it was established to be rolled back, restoring the transaction state to
-/// what it was at the time of the savepoint.
+/// what it was at the time of the savepoint. Calling `begin` on a transaction establishes an
+/// anonymous savepoint, which is itself a `Transaction`; a named savepoint established with
+/// [`savepoint`] can be rolled back to any number of times without ending the transaction.
 ///
 /// [`Connection::begin`]: crate::connection::Connection::begin()
 /// [`Pool::begin`]: crate::pool::Pool::begin()
 /// [`commit`]: Self::commit()
 /// [`rollback`]: Self::rollback()
+/// [`savepoint`]: Self::savepoint()
 pub struct Transaction<'c, DB>
 where
     DB: Database,
@@ -77,6 +311,70 @@ where
         })
     }
 
+    pub(crate) fn begin_with(
+        conn: impl Into<MaybePoolConnection<'c, DB>>,
+        options: TransactionOptions,
+    ) -> BoxFuture<'c, Result<Self, Error>> {
+        let mut conn = conn.into();
+
+        Box::pin(async move {
+            DB::TransactionManager::begin_with(&mut conn, options).await?;
+
+            Ok(Self {
+                connection: conn,
+                open: true,
+            })
+        })
+    }
+
+    /// Establish a savepoint with the given name within this transaction.
+    ///
+    /// Establishing a savepoint with the name of an existing one replaces it in PostgreSQL,
+    /// MySQL and MSSQL, while SQLite keeps both and uses the most recent.
+    ///
+    /// The name must be an identifier: ASCII letters, digits and underscores, not starting
+    /// with a digit.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: sqlx_core::postgres::PgPool) -> Result<(), sqlx_core::error::Error> {
+    /// use sqlx_core::executor::Executor;
+    ///
+    /// let mut tx = pool.begin().await?;
+    /// tx.execute("INSERT INTO orders (id) VALUES (1)").await?;
+    ///
+    /// tx.savepoint("items").await?;
+    ///
+    /// if tx.execute("INSERT INTO items (order_id) VALUES (1)").await.is_err() {
+    ///     // the order is kept
+    ///     tx.rollback_to_savepoint("items").await?;
+    /// }
+    ///
+    /// tx.commit().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn savepoint(&mut self, name: &str) -> Result<(), Error> {
+        check_savepoint_name(name)?;
+        DB::TransactionManager::savepoint(&mut self.connection, name).await
+    }
+
+    /// Undo everything executed since the named savepoint was established, without ending
+    /// this transaction. The savepoint is kept and can be rolled back to again.
+    pub async fn rollback_to_savepoint(&mut self, name: &str) -> Result<(), Error> {
+        check_savepoint_name(name)?;
+        DB::TransactionManager::rollback_to_savepoint(&mut self.connection, name).await
+    }
+
+    /// Release the named savepoint, keeping everything executed since it was established.
+    ///
+    /// Savepoints are released when the transaction ends, so this is only needed to free
+    /// their resources early, or to roll back to an earlier savepoint with the same name.
+    /// MSSQL does not release savepoints, so this does nothing there.
+    pub async fn release_savepoint(&mut self, name: &str) -> Result<(), Error> {
+        check_savepoint_name(name)?;
+        DB::TransactionManager::release_savepoint(&mut self.connection, name).await
+    }
+
     /// Commits this transaction or savepoint.
     pub async fn commit(mut self) -> Result<(), Error> {
         DB::TransactionManager::commit(&mut self.connection).await?;
@@ -214,6 +512,49 @@ where
     }
 }
 
+// savepoint names are inserted into the SQL, so only plain identifiers are accepted
+fn check_savepoint_name(name: &str) -> Result<(), Error> {
+    let mut chars = name.chars();
+
+    let valid = chars
+        .next()
+        .map_or(false, |c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::Configuration(
+            format!("invalid savepoint name {:?}", name).into(),
+        ))
+    }
+}
+
+// the error returned by the default implementations of the optional methods
+pub(crate) fn unsupported(feature: &str) -> Error {
+    Error::Configuration(format!("{} are not supported by this database driver", feature).into())
+}
+
+// a savepoint cannot have the options of a transaction
+#[cfg(any(
+    feature = "postgres",
+    feature = "mysql",
+    feature = "mssql",
+    feature = "sqlite"
+))]
+pub(crate) fn check_savepoint_options(
+    depth: usize,
+    options: &TransactionOptions,
+) -> Result<(), Error> {
+    if depth > 0 && !options.is_default() {
+        return Err(Error::Configuration(
+            "transaction options cannot be applied to a savepoint within a transaction".into(),
+        ));
+    }
+
+    Ok(())
+}
+
 #[allow(dead_code)]
 pub(crate) fn begin_ansi_transaction_sql(depth: usize) -> Cow<'static, str> {
     if depth == 0 {
@@ -243,3 +584,27 @@ pub(crate) fn rollback_ansi_transaction_sql(depth: usize) -> Cow<'static, str> {
         ))
     }
 }
+
+#[test]
+fn test_retry_policy_backoff() {
+    let policy = RetryPolicy::new()
+        .initial_backoff(Duration::from_millis(100))
+        .max_backoff(Duration::from_millis(500));
+
+    assert_eq!(policy.backoff(1), Duration::from_millis(100));
+    assert_eq!(policy.backoff(2), Duration::from_millis(200));
+    assert_eq!(policy.backoff(3), Duration::from_millis(400));
+    assert_eq!(policy.backoff(4), Duration::from_millis(500));
+    assert_eq!(policy.backoff(100), Duration::from_millis(500));
+}
+
+#[test]
+fn test_check_savepoint_name() {
+    assert!(check_savepoint_name("items").is_ok());
+    assert!(check_savepoint_name("_step_2").is_ok());
+
+    assert!(check_savepoint_name("").is_err());
+    assert!(check_savepoint_name("2nd").is_err());
+    assert!(check_savepoint_name("a; DROP TABLE b").is_err());
+    assert!(check_savepoint_name("\"quoted\"").is_err());
+}
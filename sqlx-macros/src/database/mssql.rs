@@ -12,6 +12,21 @@ impl_database_ext! {
         f64,
         String,
         // String |ntext,
+        #[cfg(feature = "uuid")]
+        sqlx::types::Uuid,
+
+        #[cfg(feature = "time")]
+        sqlx::types::time::Time,
+
+        #[cfg(feature = "time")]
+        sqlx::types::time::Date,
+
+        #[cfg(feature = "time")]
+        sqlx::types::time::PrimitiveDateTime,
+
+        #[cfg(feature = "time")]
+        sqlx::types::time::OffsetDateTime,
+
         #[cfg(all(feature = "chrono", not(feature = "time")))]
         sqlx::types::chrono::NaiveTime,
 
@@ -25,9 +40,13 @@ impl_database_ext! {
         sqlx::types::chrono::DateTime<sqlx::types::chrono::Utc>,
 
         BigDecimal,
+
+        sqlx::mssql::types::MssqlVarChar,
+
+        sqlx::mssql::types::MssqlXml,
     },
     ParamChecking::Weak,
-    feature-types: _info => None,
+    feature-types: info => info.__type_feature_gate(),
     row = sqlx::mssql::MssqlRow,
     name = "MSSQL"
 }
@@ -39,3 +39,35 @@ async fn it_describes_with_params() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_describes_parameter_types() -> anyhow::Result<()> {
+    let mut conn = new::<Mssql>().await?;
+
+    let d = conn
+        .describe("SELECT id FROM tweet WHERE text = @p2 AND owner_id = @p1")
+        .await?;
+
+    let parameters = d.parameters().unwrap().left().unwrap();
+
+    assert_eq!(parameters.len(), 2);
+    assert_eq!(parameters[0].name(), "BIGINT");
+    assert_eq!(parameters[1].name(), "NVARCHAR");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_describes_expression_nullability() -> anyhow::Result<()> {
+    let mut conn = new::<Mssql>().await?;
+
+    let d = conn
+        .describe("SELECT id, owner_id, ISNULL(owner_id, 0) AS owner FROM tweet")
+        .await?;
+
+    assert_eq!(d.nullable(0), Some(false));
+    assert_eq!(d.nullable(1), Some(true));
+    assert_eq!(d.nullable(2), Some(false));
+
+    Ok(())
+}
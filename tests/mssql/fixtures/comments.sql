@@ -0,0 +1,16 @@
+insert into comment(comment_id, post_id, user_id, content, created_at)
+values (1,
+        1,
+        2,
+        'lol bet ur still bad, 1v1 me',
+        dateadd(minute, -50, sysutcdatetime())),
+       (2,
+        1,
+        1,
+        'you''re on!',
+        dateadd(minute, -45, sysutcdatetime())),
+       (3,
+        2,
+        1,
+        'lol you''re just mad you lost :P',
+        dateadd(minute, -15, sysutcdatetime()));
@@ -0,0 +1,9 @@
+insert into post(post_id, user_id, content, created_at)
+values (1,
+        1,
+        'This new computer is lightning-fast!',
+        dateadd(minute, -60, sysutcdatetime())),
+       (2,
+        2,
+        '@alice is a haxxor :(',
+        dateadd(minute, -30, sysutcdatetime()));
@@ -0,0 +1,6 @@
+create table [user]
+(
+    user_id  integer primary key,
+    -- indexed text values have to have a max length
+    username nvarchar(16) unique not null
+);
@@ -0,0 +1,9 @@
+create table post
+(
+    post_id    integer primary key,
+    user_id    integer       not null references [user] (user_id),
+    content    nvarchar(max) not null,
+    created_at datetime2 default sysutcdatetime()
+);
+
+create index post_created_at on post (created_at desc);
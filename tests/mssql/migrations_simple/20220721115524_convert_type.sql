@@ -0,0 +1,18 @@
+-- Perform a conversion of the payload.
+--
+-- This script will only succeed once and will fail if executed twice.
+
+-- T-SQL resolves column names when the batch is compiled, so the new column is
+-- only referenced from a nested batch.
+ALTER TABLE migrations_simple_test
+ADD some_payload_tmp NVARCHAR(100);
+
+EXEC(N'UPDATE migrations_simple_test
+SET some_payload_tmp = CONCAT(CAST((some_payload + 10) AS NVARCHAR(3)), ''_suffix'')');
+
+-- remove original column including the content
+ALTER TABLE migrations_simple_test
+DROP COLUMN some_payload;
+
+-- rename the new column into place
+EXEC sp_rename 'migrations_simple_test.some_payload_tmp', 'some_payload', 'COLUMN';
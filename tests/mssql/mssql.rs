@@ -1,6 +1,11 @@
 use futures::TryStreamExt;
-use sqlx::mssql::{Mssql, MssqlPoolOptions};
-use sqlx::{Column, Connection, Executor, MssqlConnection, Row, Statement, TypeInfo};
+use sqlx::error::ErrorKind;
+use sqlx::mssql::types::{MssqlPlpReader, MssqlVarChar, MssqlXml, TableValuedParameter};
+use sqlx::mssql::{Mssql, MssqlConnectOptions, MssqlPoolOptions};
+use sqlx::{
+    Column, ConnectOptions, Connection, Executor, IsolationLevel, MssqlConnection, QueryBuilder,
+    Row, Statement, TransactionOptions, TypeInfo,
+};
 use sqlx_core::mssql::MssqlRow;
 use sqlx_test::new;
 use std::sync::atomic::{AtomicI32, Ordering};
@@ -297,6 +302,100 @@ async fn it_can_work_with_nested_transactions() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_can_begin_with_an_isolation_level() -> anyhow::Result<()> {
+    let mut conn = new::<Mssql>().await?;
+
+    let query = "SELECT CAST(transaction_isolation_level AS INT) FROM sys.dm_exec_sessions \
+                 WHERE session_id = @@SPID";
+
+    let mut tx = conn
+        .begin_with(TransactionOptions::new().isolation_level(IsolationLevel::Serializable))
+        .await?;
+
+    let level: i32 = sqlx::query_scalar(query).fetch_one(&mut tx).await?;
+
+    // SERIALIZABLE
+    assert_eq!(level, 4);
+
+    tx.commit().await?;
+
+    let level: i32 = sqlx::query_scalar(query).fetch_one(&mut conn).await?;
+
+    // back to READ COMMITTED once the transaction ends
+    assert_eq!(level, 2);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_rollback_to_a_savepoint() -> anyhow::Result<()> {
+    let mut conn = new::<Mssql>().await?;
+
+    conn.execute("CREATE TABLE #savepoints (id INTEGER PRIMARY KEY)")
+        .await?;
+
+    let mut tx = conn.begin().await?;
+
+    tx.execute("INSERT INTO #savepoints (id) VALUES (1)")
+        .await?;
+
+    tx.savepoint("after_first").await?;
+
+    for _ in 0..2 {
+        tx.execute("INSERT INTO #savepoints (id) VALUES (2)")
+            .await?;
+
+        // the savepoint is kept, so it can be rolled back to again
+        tx.rollback_to_savepoint("after_first").await?;
+    }
+
+    tx.release_savepoint("after_first").await?;
+    tx.commit().await?;
+
+    let count: i32 = sqlx::query_scalar("SELECT COUNT(*) FROM #savepoints")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_classifies_constraint_errors() -> anyhow::Result<()> {
+    let mut conn = new::<Mssql>().await?;
+
+    conn.execute(
+        "CREATE TABLE #kinds (id INT PRIMARY KEY, name NVARCHAR(10) NOT NULL CHECK (name != ''))",
+    )
+    .await?;
+
+    conn.execute("INSERT INTO #kinds (id, name) VALUES (1, 'a')")
+        .await?;
+
+    for (query, kind) in [
+        (
+            "INSERT INTO #kinds (id, name) VALUES (1, 'b')",
+            ErrorKind::UniqueViolation,
+        ),
+        (
+            "INSERT INTO #kinds (id, name) VALUES (2, NULL)",
+            ErrorKind::NotNullViolation,
+        ),
+        (
+            "INSERT INTO #kinds (id, name) VALUES (2, '')",
+            ErrorKind::CheckViolation,
+        ),
+    ] {
+        let err = conn.execute(query).await.unwrap_err();
+
+        assert_eq!(err.as_database_error().unwrap().kind(), kind, "{}", query);
+    }
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_can_prepare_then_execute() -> anyhow::Result<()> {
     let mut conn = new::<Mssql>().await?;
@@ -328,6 +427,284 @@ async fn it_can_prepare_then_execute() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_calls_a_procedure_with_output_parameters() -> anyhow::Result<()> {
+    let mut conn = new::<Mssql>().await?;
+
+    conn.execute(
+        r#"
+CREATE PROCEDURE #sqlx_add (
+    @a INT,
+    @b INT,
+    @sum INT OUTPUT,
+    @label NVARCHAR(50) OUTPUT,
+    @summary NVARCHAR(100) OUTPUT
+)
+AS
+BEGIN
+    SELECT @a AS a, @b AS b;
+    SET @sum = @a + @b;
+    SET @label = CONCAT(@label, ': ', @sum);
+    SET @summary = CONCAT(@a, ' + ', @b, ' = ', @sum);
+    RETURN 7;
+END
+        "#,
+    )
+    .await?;
+
+    let result = conn
+        .call_procedure("#sqlx_add")
+        .bind(2_i32)
+        .bind_named("@b", 3_i32)
+        .bind_output::<i32>("@sum")
+        .bind_inout("label", "total")
+        .bind_output::<String>("@summary")
+        .execute()
+        .await?;
+
+    assert_eq!(result.return_status(), Some(7));
+    assert_eq!(result.try_get::<i32>("@sum")?, 5);
+    assert_eq!(result.try_get::<String>("label")?, "total: 5");
+    assert_eq!(result.try_get::<String>("summary")?, "2 + 3 = 5");
+
+    assert_eq!(result.rows().len(), 1);
+    assert_eq!(result.rows()[0].try_get::<i32, _>("b")?, 3);
+
+    // the connection should remain usable after the call
+    let v: i32 = sqlx::query_scalar("SELECT 4").fetch_one(&mut conn).await?;
+    assert_eq!(v, 4);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_binds_a_table_valued_parameter() -> anyhow::Result<()> {
+    let mut conn = new::<Mssql>().await?;
+
+    conn.execute(
+        r#"
+IF TYPE_ID(N'dbo.sqlx_user_list') IS NULL
+CREATE TYPE dbo.sqlx_user_list AS TABLE (id INT NOT NULL, name NVARCHAR(100) NULL);
+        "#,
+    )
+    .await?;
+
+    let mut users = TableValuedParameter::new("dbo.sqlx_user_list")
+        .column::<i32>()
+        .column::<String>();
+
+    for id in 0..1000 {
+        users.add_row().push(id).push(format!("user {}", id));
+    }
+
+    users.add_row().push(1000_i32).push(Option::<String>::None);
+
+    let (count, nulls): (i32, i32) =
+        sqlx::query_as("SELECT COUNT(*), SUM(CASE WHEN name IS NULL THEN 1 ELSE 0 END) FROM @p1")
+            .bind(users.clone())
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(count, 1001);
+    assert_eq!(nulls, 1);
+
+    let mut builder = QueryBuilder::new("SELECT name FROM ");
+    builder
+        .push_bind(users)
+        .push(" WHERE id = ")
+        .push_bind(42_i32);
+
+    let (name,): (String,) = builder.build_query_as().fetch_one(&mut conn).await?;
+
+    assert_eq!(name, "user 42");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_bulk_loads_rows() -> anyhow::Result<()> {
+    let mut conn = new::<Mssql>().await?;
+
+    conn.execute(
+        r#"
+CREATE TABLE #sqlx_bulk_load (id INT NOT NULL PRIMARY KEY, name NVARCHAR(100) NULL, data VARBINARY(MAX) NULL);
+        "#,
+    )
+    .await?;
+
+    let mut load = conn
+        .bulk_load("#sqlx_bulk_load")
+        .column::<i32>("id")
+        .column::<String>("name")
+        .column::<Vec<u8>>("data")
+        .tablock(true)
+        .check_constraints(true)
+        .batch_size(1000);
+
+    for id in 0..2500 {
+        load.add_row()
+            .await?
+            .push(id)
+            .push(format!("user {}", id))
+            .push(vec![id as u8; 16]);
+    }
+
+    load.add_row()
+        .await?
+        .push(2500_i32)
+        .push(Option::<String>::None)
+        .push(Option::<Vec<u8>>::None);
+
+    assert_eq!(load.finish().await?, 2501);
+
+    let (count, nulls): (i32, i32) = sqlx::query_as(
+        "SELECT COUNT(*), SUM(CASE WHEN name IS NULL THEN 1 ELSE 0 END) FROM #sqlx_bulk_load",
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(count, 2501);
+    assert_eq!(nulls, 1);
+
+    let (name, data): (String, Vec<u8>) =
+        sqlx::query_as("SELECT name, data FROM #sqlx_bulk_load WHERE id = @p1")
+            .bind(42_i32)
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(name, "user 42");
+    assert_eq!(data, vec![42_u8; 16]);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_cancels_a_dropped_query() -> anyhow::Result<()> {
+    let mut conn = new::<Mssql>().await?;
+
+    {
+        let mut s =
+            conn.fetch("SELECT a.object_id FROM sys.all_objects a CROSS JOIN sys.all_objects b");
+
+        // read a single row and then drop the stream while the server is still sending rows
+        assert!(s.try_next().await?.is_some());
+    }
+
+    let v: i32 = sqlx::query_scalar("SELECT 42").fetch_one(&mut conn).await?;
+    assert_eq!(v, 42);
+
+    {
+        let mut s = conn.fetch("WAITFOR DELAY '00:00:30'; SELECT 1");
+
+        // drop the future before the first row arrives, as a timeout would
+        let _ = futures::poll!(s.try_next());
+    }
+
+    conn.cancel().await?;
+
+    let mut tx = conn.begin().await?;
+    let v: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut *tx).await?;
+    assert_eq!(v, 1);
+    tx.rollback().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reads_max_values_in_chunks() -> anyhow::Result<()> {
+    use std::io::Read;
+
+    let mut conn = new::<Mssql>().await?;
+
+    // large enough that the server sends the value in more than one chunk
+    let row = sqlx::query(
+        "SELECT REPLICATE(CAST('a' AS VARCHAR(MAX)), 100000), \
+         REPLICATE(CAST(N'b' AS NVARCHAR(MAX)), 100000), CAST('' AS VARCHAR(MAX))",
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    let mut bytes = Vec::new();
+    let mut reader: MssqlPlpReader<'_> = row.try_get(0)?;
+    reader.read_to_end(&mut bytes)?;
+
+    assert_eq!(bytes.len(), 100000);
+    assert!(bytes.iter().all(|&b| b == b'a'));
+
+    let s: String = row.try_get(1)?;
+    assert_eq!(s.len(), 100000);
+    assert!(s.chars().all(|c| c == 'b'));
+
+    let empty: String = row.try_get(2)?;
+    assert_eq!(empty, "");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_binds_xml() -> anyhow::Result<()> {
+    let mut conn = new::<Mssql>().await?;
+
+    let xml: MssqlXml = sqlx::query_scalar("SELECT @p1")
+        .bind(MssqlXml("<a><b>c</b></a>".into()))
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(&*xml, "<a><b>c</b></a>");
+
+    let name: String = sqlx::query_scalar("SELECT @p1.value('(/a/b)[1]', 'nvarchar(10)')")
+        .bind(MssqlXml("<a><b>c</b></a>".into()))
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(name, "c");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_binds_varchar_in_the_database_collation() -> anyhow::Result<()> {
+    let mut conn = new::<Mssql>().await?;
+
+    conn.execute("CREATE TABLE #legacy (name VARCHAR(20) COLLATE DATABASE_DEFAULT NOT NULL)")
+        .await?;
+
+    sqlx::query("INSERT INTO #legacy (name) VALUES (@p1)")
+        .bind(MssqlVarChar::from("café"))
+        .execute(&mut conn)
+        .await?;
+
+    let name: String = sqlx::query_scalar("SELECT name FROM #legacy WHERE name = @p1")
+        .bind(MssqlVarChar::from("café"))
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(name, "café");
+
+    let mut load = conn.bulk_load("#legacy").column::<MssqlVarChar>("name");
+    load.add_row()
+        .await?
+        .push(MssqlVarChar::from("crème brûlée"));
+    load.finish().await?;
+
+    let count: i32 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM #legacy WHERE name = N'crème brûlée'")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(count, 1);
+
+    // longer than `varchar(8000)`
+    let len: i64 = sqlx::query_scalar("SELECT CAST(DATALENGTH(@p1) AS BIGINT)")
+        .bind(MssqlVarChar("x".repeat(9000)))
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(len, 9000);
+
+    Ok(())
+}
+
 // MSSQL-specific copy of the test case in `tests/any/pool.rs`
 // because MSSQL has its own bespoke syntax for temporary tables.
 #[sqlx_macros::test]
@@ -459,3 +836,53 @@ async fn test_pool_callbacks() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_runs_requests_on_multiple_active_result_sets() -> anyhow::Result<()> {
+    let options: MssqlConnectOptions = dotenvy::var("DATABASE_URL")?.parse()?;
+    let mut conn = options.multiple_active_result_sets(true).connect().await?;
+
+    conn.execute(
+        r#"
+CREATE TABLE #jobs (id INT PRIMARY KEY, done BIT NOT NULL DEFAULT 0);
+INSERT INTO #jobs (id) SELECT TOP 1000 ROW_NUMBER() OVER (ORDER BY (SELECT 1)) FROM sys.all_columns;
+            "#,
+    )
+    .await?;
+
+    let mut session = conn.open_session().await?;
+
+    {
+        let mut rows = sqlx::query("SELECT id FROM #jobs ORDER BY id").fetch(&mut conn);
+        let mut count = 0;
+
+        // update every row while the query is still streaming them
+        while let Some(row) = rows.try_next().await? {
+            sqlx::query("UPDATE #jobs SET done = 1 WHERE id = @p1")
+                .bind(row.try_get::<i32, _>(0)?)
+                .execute(&mut session)
+                .await?;
+
+            count += 1;
+        }
+
+        assert_eq!(count, 1000);
+    }
+
+    let done: i32 = sqlx::query_scalar("SELECT COUNT(*) FROM #jobs WHERE done = 1")
+        .fetch_one(&mut session)
+        .await?;
+
+    assert_eq!(done, 1000);
+
+    session.close().await?;
+
+    // the connection keeps working after one of its sessions is closed
+    conn.ping().await?;
+    conn.close().await?;
+
+    let mut conn = new::<Mssql>().await?;
+    assert!(conn.open_session().await.is_err());
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
+use sqlx::mssql::types::{MssqlVarChar, MssqlXml};
 use sqlx::mssql::Mssql;
-use sqlx_test::test_type;
+use sqlx_test::{test_decode_type, test_type};
 
 test_type!(null<Option<i32>>(Mssql,
     "CAST(NULL as INT)" == None::<i32>
@@ -43,8 +44,98 @@ test_type!(str<String>(Mssql,
     "''" == "",
 ));
 
+test_decode_type!(str_varchar_collation<String>(Mssql,
+    "CAST(N'café €' COLLATE Latin1_General_CI_AS AS VARCHAR(10))" == "café €",
+    "CAST(N'中文' COLLATE Chinese_PRC_CI_AS AS VARCHAR(10))" == "中文",
+    "CAST(N'日本語' COLLATE Japanese_CI_AS AS VARCHAR(10))" == "日本語",
+    "CAST(N'привет' COLLATE Cyrillic_General_CI_AS AS VARCHAR(10))" == "привет",
+    "CAST(N'zażółć' COLLATE Polish_CI_AS AS CHAR(6))" == "zażółć",
+));
+
+test_type!(varchar<MssqlVarChar>(Mssql,
+    "CAST('this is foo' AS VARCHAR(20))" == MssqlVarChar::from("this is foo"),
+    "CAST(N'café' AS VARCHAR(20))" == MssqlVarChar::from("café"),
+));
+
 test_type!(bool(
     Mssql,
     "CAST(1 as BIT)" == true,
     "CAST(0 as BIT)" == false
 ));
+
+test_decode_type!(xml<MssqlXml>(Mssql,
+    "CAST('<a>b</a>' AS XML)" == MssqlXml("<a>b</a>".into()),
+    "CAST(N'<a><b/></a>' AS XML)" == MssqlXml("<a><b/></a>".into()),
+));
+
+#[cfg(feature = "uuid")]
+test_type!(uuid<sqlx::types::Uuid>(Mssql,
+    "CAST('6F9619FF-8B86-D011-B42D-00C04FC964FF' AS UNIQUEIDENTIFIER)"
+        == sqlx::types::Uuid::parse_str("6f9619ff-8b86-d011-b42d-00c04fc964ff").unwrap(),
+    "CAST('00000000-0000-0000-0000-000000000000' AS UNIQUEIDENTIFIER)"
+        == sqlx::types::Uuid::nil(),
+));
+
+#[cfg(feature = "time")]
+mod time_tests {
+    use super::*;
+    use sqlx::types::time::{Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+    use time::macros::{date, time};
+
+    test_type!(time_date<Date>(
+        Mssql,
+        "CAST('2001-01-05' AS DATE)" == date!(2001 - 1 - 5),
+        "CAST('2050-11-23' AS DATE)" == date!(2050 - 11 - 23)
+    ));
+
+    test_type!(time_time<Time>(
+        Mssql,
+        "CAST('05:10:20.1151000' AS TIME)" == time!(5:10:20.1151),
+        "CAST('05:10:20.115' AS TIME(3))" == time!(5:10:20.115),
+        "CAST('05:10:20' AS TIME(0))" == time!(5:10:20)
+    ));
+
+    test_type!(time_date_time<PrimitiveDateTime>(
+        Mssql,
+        "CAST('2019-01-02 05:10:20' AS DATETIME2)" == date!(2019 - 1 - 2).with_time(time!(5:10:20)),
+        "CAST('2019-01-02 05:10:20.1151' AS DATETIME2(4))" == date!(2019 - 1 - 2).with_time(time!(5:10:20.1151)),
+        "CAST('2019-01-02 05:10:20' AS DATETIME)" == date!(2019 - 1 - 2).with_time(time!(5:10:20))
+    ));
+
+    test_type!(time_date_time_offset<OffsetDateTime>(
+        Mssql,
+        "CAST('2019-01-02 05:10:20.1151 +00:00' AS DATETIMEOFFSET)"
+            == date!(2019 - 1 - 2)
+                .with_time(time!(5:10:20.1151))
+                .assume_utc(),
+        "CAST('2019-01-02 05:10:20 +06:30' AS DATETIMEOFFSET)"
+            == date!(2019 - 1 - 2)
+                .with_time(time!(5:10:20))
+                .assume_offset(UtcOffset::from_whole_seconds(60 * 60 * 6 + 1800).unwrap())
+    ));
+}
+
+#[cfg(feature = "json")]
+mod json {
+    use super::*;
+    use serde_json::{json, Value as JsonValue};
+    use sqlx::types::Json;
+
+    test_type!(json<JsonValue>(
+        Mssql,
+        "N'\"Hello, World\"'" == json!("Hello, World"),
+        "N'{\"name\":\"Joe\",\"age\":33}'" == json!({"name": "Joe", "age": 33}),
+        "N'[1,2,3]'" == json!([1, 2, 3]),
+    ));
+
+    #[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq)]
+    struct Friend {
+        name: String,
+        age: u32,
+    }
+
+    test_type!(json_struct<Json<Friend>>(
+        Mssql,
+        "N'{\"name\":\"Joe\",\"age\":33}'" == Json(Friend { name: "Joe".to_string(), age: 33 }),
+    ));
+}
@@ -1,10 +1,14 @@
 use futures::{StreamExt, TryStreamExt};
+use sqlx::error::ErrorKind;
 use sqlx::postgres::types::Oid;
 use sqlx::postgres::{
     PgAdvisoryLock, PgConnectOptions, PgConnection, PgDatabaseError, PgErrorPosition, PgListener,
-    PgPoolOptions, PgRow, PgSeverity, Postgres,
+    PgLsn, PgPoolOptions, PgReplicationMessage, PgRow, PgSeverity, PgTargetSessionAttrs, Postgres,
+};
+use sqlx::{
+    Column, Connection, Executor, IsolationLevel, RetryPolicy, Row, Statement, TransactionOptions,
+    TypeInfo,
 };
-use sqlx::{Column, Connection, Executor, Row, Statement, TypeInfo};
 use sqlx_test::{new, pool, setup_if_needed};
 use std::env;
 use std::sync::Arc;
@@ -105,6 +109,7 @@ async fn it_can_inspect_errors() -> anyhow::Result<()> {
 
     assert_eq!(err.message(), "column \"f\" does not exist");
     assert_eq!(err.code().as_deref(), Some("42703"));
+    assert_eq!(err.kind(), ErrorKind::Other);
 
     // can also do [downcast_ref]
     let err: Box<PgDatabaseError> = err.downcast();
@@ -137,6 +142,8 @@ async fn it_can_inspect_constraint_errors() -> anyhow::Result<()> {
         "new row for relation \"products\" violates check constraint \"products_price_check\""
     );
     assert_eq!(err.code().as_deref(), Some("23514"));
+    assert_eq!(err.kind(), ErrorKind::CheckViolation);
+    assert_eq!(err.table(), Some("products"));
 
     // can also do [downcast_ref]
     let err: Box<PgDatabaseError> = err.downcast();
@@ -502,6 +509,135 @@ async fn it_can_work_with_nested_transactions() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_can_begin_with_transaction_options() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let mut tx = conn
+        .begin_with(
+            TransactionOptions::new()
+                .isolation_level(IsolationLevel::Serializable)
+                .read_only(true),
+        )
+        .await?;
+
+    let isolation: String = sqlx::query_scalar("SHOW transaction_isolation")
+        .fetch_one(&mut tx)
+        .await?;
+
+    assert_eq!(isolation, "serializable");
+
+    let read_only: String = sqlx::query_scalar("SHOW transaction_read_only")
+        .fetch_one(&mut tx)
+        .await?;
+
+    assert_eq!(read_only, "on");
+
+    // options cannot be applied to a savepoint
+    assert!(tx
+        .begin_with(TransactionOptions::new().read_only(true))
+        .await
+        .is_err());
+
+    tx.rollback().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_rollback_to_a_savepoint() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE _sqlx_savepoints (id INTEGER PRIMARY KEY)")
+        .await?;
+
+    let mut tx = conn.begin().await?;
+
+    tx.execute("INSERT INTO _sqlx_savepoints (id) VALUES (1)")
+        .await?;
+
+    tx.savepoint("after_first").await?;
+
+    for _ in 0..2 {
+        tx.execute("INSERT INTO _sqlx_savepoints (id) VALUES (2)")
+            .await?;
+
+        // the savepoint is kept, so it can be rolled back to again
+        tx.rollback_to_savepoint("after_first").await?;
+    }
+
+    // a unique violation aborts the transaction until it is rolled back to a savepoint
+    assert!(tx
+        .execute("INSERT INTO _sqlx_savepoints (id) VALUES (1)")
+        .await
+        .is_err());
+
+    tx.rollback_to_savepoint("after_first").await?;
+    tx.release_savepoint("after_first").await?;
+
+    assert!(tx.savepoint("not a name").await.is_err());
+
+    tx.commit().await?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM _sqlx_savepoints")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_retries_transactions_on_serialization_failures() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let policy = RetryPolicy::new().initial_backoff(Duration::from_millis(1));
+    let mut attempts = 0;
+
+    let attempt = conn
+        .transaction_with_retry(TransactionOptions::new(), policy, |tx| {
+            attempts += 1;
+            let attempt = attempts;
+
+            Box::pin(async move {
+                if attempt < 3 {
+                    tx.execute("DO $$ BEGIN RAISE EXCEPTION SQLSTATE '40001'; END $$")
+                        .await?;
+                }
+
+                Ok(attempt)
+            })
+        })
+        .await?;
+
+    assert_eq!(attempt, 3);
+
+    // the error is returned once the attempts run out
+    let mut attempts = 0;
+
+    let res = conn
+        .transaction_with_retry(TransactionOptions::new(), policy.max_attempts(2), |tx| {
+            attempts += 1;
+
+            Box::pin(async move {
+                tx.execute("DO $$ BEGIN RAISE EXCEPTION SQLSTATE '40P01'; END $$")
+                    .await?;
+
+                Ok(())
+            })
+        })
+        .await;
+
+    let err = res.unwrap_err().into_database_error().unwrap();
+
+    assert!(err.is_retryable());
+    assert_eq!(err.code().as_deref(), Some("40P01"));
+    assert_eq!(attempts, 2);
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_can_drop_multiple_transactions() -> anyhow::Result<()> {
     let mut conn = new::<Postgres>().await?;
@@ -1493,6 +1629,60 @@ async fn it_can_copy_out() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_can_copy_in_and_out_binary() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+    conn.execute(
+        r#"
+        CREATE TEMPORARY TABLE users (id INTEGER NOT NULL, name TEXT);
+    "#,
+    )
+    .await?;
+
+    let mut copy = conn.copy_in_binary("COPY users (id, name) FROM STDIN (FORMAT BINARY)");
+
+    copy.write_row((1_i32, "alice")).await?;
+    copy.write_row((2_i32, None::<String>)).await?;
+
+    let rows = copy.finish().await?;
+    assert_eq!(rows, 2);
+
+    {
+        let records: Vec<_> = conn
+            .copy_out_binary("COPY (SELECT * FROM users ORDER BY id) TO STDOUT (FORMAT BINARY)")
+            .await?
+            .try_collect()
+            .await?;
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].get::<i32>(0), 1);
+        assert_eq!(
+            records[0].get::<Option<String>>(1).as_deref(),
+            Some("alice")
+        );
+        assert_eq!(records[1].get::<i32>(0), 2);
+        assert_eq!(records[1].get::<Option<String>>(1), None);
+    }
+
+    // a textual COPY fails to start, and so does every call after it
+    let name = "x".repeat(100_000);
+    let mut copy = conn.copy_in_binary("COPY users (id, name) FROM STDIN");
+
+    assert!(copy.write_row((3_i32, &*name)).await.is_err());
+    assert!(copy.write_row((4_i32, &*name)).await.is_err());
+    assert!(copy.finish().await.is_err());
+
+    // conn is safe for reuse
+    let value = sqlx::query("select 1 + 1")
+        .try_map(|row: PgRow| row.try_get::<i32, _>(0))
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(2i32, value);
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_encodes_custom_array_issue_1504() -> anyhow::Result<()> {
     use sqlx::encode::IsNull;
@@ -1726,3 +1916,248 @@ async fn test_postgres_bytea_hex_deserialization_errors() -> anyhow::Result<()>
     }
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_streams_changes_from_a_replication_slot() -> anyhow::Result<()> {
+    sqlx_test::setup_if_needed();
+
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute("CREATE TABLE IF NOT EXISTS _sqlx_replication (id INTEGER PRIMARY KEY)")
+        .await?;
+
+    conn.execute("DELETE FROM _sqlx_replication").await?;
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let mut replication = PgConnection::connect_with(&options.replication(true)).await?;
+
+    // dropped when the connection is closed
+    let slot = replication
+        .create_replication_slot("_sqlx_test_slot", "test_decoding", true)
+        .await?;
+
+    assert_eq!(slot.name(), "_sqlx_test_slot");
+    assert!(slot.consistent_point() > PgLsn(0));
+
+    conn.execute("INSERT INTO _sqlx_replication (id) VALUES (1)")
+        .await?;
+
+    let mut stream = replication
+        .start_replication(slot.name(), PgLsn(0), &[("include-xids", "0")])
+        .await?;
+
+    let change = loop {
+        match stream.recv().await?.expect("replication stream ended") {
+            PgReplicationMessage::XLogData(data) => {
+                assert!(data.wal_start() >= slot.consistent_point());
+
+                let change = String::from_utf8(data.data().to_vec())?;
+                stream.acknowledge(data.wal_start()).await?;
+
+                // other tests may be writing at the same time
+                if change.contains("_sqlx_replication") {
+                    break change;
+                }
+            }
+
+            PgReplicationMessage::Keepalive(_) => stream.send_status_update().await?,
+        }
+    };
+
+    assert_eq!(
+        change,
+        "table public._sqlx_replication: INSERT: id[integer]:1"
+    );
+
+    stream.finish().await?;
+
+    // the connection accepts replication commands again
+    replication.execute("IDENTIFY_SYSTEM").await?;
+    replication.close().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_cancel_a_running_query() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+    let token = conn.cancel_token();
+
+    sqlx_rt::spawn(async move {
+        sqlx_rt::sleep(Duration::from_secs(1)).await;
+        token.cancel().await.unwrap();
+    });
+
+    let err = conn.execute("SELECT pg_sleep(10)").await.unwrap_err();
+    let err = err.into_database_error().unwrap();
+
+    assert_eq!(err.code().as_deref(), Some("57014"));
+    assert_eq!(err.kind(), ErrorKind::Timeout);
+
+    // the connection is still usable
+    let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+    assert_eq!(value, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_cancels_dropped_queries_with_cancel_on_drop() -> anyhow::Result<()> {
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
+    let mut conn = PgConnection::connect_with(&options.cancel_on_drop(true)).await?;
+
+    let res = sqlx_rt::timeout(Duration::from_secs(1), conn.execute("SELECT pg_sleep(30)")).await;
+
+    assert!(res.is_err());
+
+    // without the cancel request, this would wait for the sleep to finish
+    let started = std::time::Instant::now();
+    let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+
+    assert_eq!(value, 1);
+    assert!(started.elapsed() < Duration::from_secs(10));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_executes_a_pipeline() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE _sqlx_pipeline (id INTEGER PRIMARY KEY)")
+        .await?;
+
+    let mut pipeline = conn.pipeline();
+
+    for id in 0..100_i32 {
+        pipeline.push(sqlx::query("INSERT INTO _sqlx_pipeline (id) VALUES ($1)").bind(id));
+    }
+
+    pipeline.push("SELECT id FROM _sqlx_pipeline WHERE id < 3 ORDER BY id");
+    assert_eq!(pipeline.len(), 101);
+
+    let outputs = pipeline.execute().await?;
+    assert_eq!(outputs.len(), 101);
+    assert!(outputs[..100].iter().all(|o| o.rows_affected() == 1));
+
+    let ids: Vec<i32> = outputs[100].rows().iter().map(|row| row.get(0)).collect();
+    assert_eq!(ids, [0, 1, 2]);
+
+    // a failing query skips the rest of the pipeline, and rolls back the queries before it
+    let mut pipeline = conn.pipeline();
+    pipeline
+        .push(sqlx::query("INSERT INTO _sqlx_pipeline (id) VALUES ($1)").bind(100_i32))
+        .push(sqlx::query("INSERT INTO _sqlx_pipeline (id) VALUES ($1)").bind(0_i32))
+        .push(sqlx::query("INSERT INTO _sqlx_pipeline (id) VALUES ($1)").bind(101_i32));
+
+    let err = pipeline.execute().await.unwrap_err();
+    assert_eq!(
+        err.into_database_error().unwrap().kind(),
+        ErrorKind::UniqueViolation
+    );
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM _sqlx_pipeline")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, 100);
+
+    // unless each query is executed on its own
+    let mut pipeline = conn.pipeline();
+    pipeline
+        .push(sqlx::query("INSERT INTO _sqlx_pipeline (id) VALUES ($1)").bind(100_i32))
+        .push(sqlx::query("INSERT INTO _sqlx_pipeline (id) VALUES ($1)").bind(0_i32))
+        .push(sqlx::query("INSERT INTO _sqlx_pipeline (id) VALUES ($1)").bind(101_i32));
+
+    let results = pipeline.execute_each().await?;
+    assert_eq!(results.len(), 3);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+    assert!(results[2].is_ok());
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM _sqlx_pipeline")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, 102);
+
+    // more queries and results than the socket buffers hold
+    let text = "x".repeat(1000);
+    let mut pipeline = conn.pipeline();
+
+    for _ in 0..2000 {
+        pipeline.push(sqlx::query("SELECT repeat($1, 10)").bind(&text));
+    }
+
+    let outputs = pipeline.execute().await?;
+    assert_eq!(outputs.len(), 2000);
+    assert!(outputs
+        .iter()
+        .all(|o| o.rows()[0].get::<String, _>(0).len() == 10_000));
+
+    // a failing query in a later chunk
+    let mut pipeline = conn.pipeline();
+
+    for _ in 0..2000 {
+        pipeline.push(sqlx::query("SELECT repeat($1, 10)").bind(&text));
+    }
+
+    pipeline.push(sqlx::query("INSERT INTO _sqlx_pipeline (id) VALUES ($1)").bind(0_i32));
+    pipeline.push(sqlx::query("SELECT repeat($1, 10)").bind(&text));
+
+    assert!(pipeline.execute().await.is_err());
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM _sqlx_pipeline")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, 102);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_connects_to_the_next_host_with_target_session_attrs() -> anyhow::Result<()> {
+    let url = url::Url::parse(&env::var("DATABASE_URL")?)?;
+    let host = format!("{}:{}", url.host_str().unwrap(), url.port().unwrap_or(5432));
+
+    // nothing listens on the first host
+    let mut unreachable = url.clone();
+    unreachable.set_host(Some("127.0.0.1")).unwrap();
+    unreachable.set_port(Some(1)).unwrap();
+
+    // `Url` cannot hold more than one host
+    let url = unreachable
+        .as_str()
+        .replacen("127.0.0.1:1", &format!("127.0.0.1:1,{}", host), 1);
+
+    let options: PgConnectOptions = url.parse()?;
+
+    let mut conn = PgConnection::connect_with(
+        &options
+            .clone()
+            .target_session_attrs(PgTargetSessionAttrs::ReadWrite),
+    )
+    .await?;
+
+    let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+    assert_eq!(value, 1);
+
+    conn.close().await?;
+
+    // the test server is not a standby
+    let res = PgConnection::connect_with(
+        &options
+            .clone()
+            .target_session_attrs(PgTargetSessionAttrs::Standby),
+    )
+    .await;
+    assert!(res.is_err());
+
+    // but it is good enough when a standby is only preferred
+    let conn = PgConnection::connect_with(
+        &options.target_session_attrs(PgTargetSessionAttrs::PreferStandby),
+    )
+    .await?;
+
+    conn.close().await?;
+
+    Ok(())
+}
@@ -1,10 +1,11 @@
 use futures::TryStreamExt;
 use rand::{Rng, SeedableRng};
 use rand_xoshiro::Xoshiro256PlusPlus;
+use sqlx::error::ErrorKind;
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 use sqlx::{
-    query, sqlite::Sqlite, sqlite::SqliteRow, Column, ConnectOptions, Connection, Executor, Row,
-    SqliteConnection, SqlitePool, Statement, TypeInfo,
+    query, sqlite::Sqlite, sqlite::SqliteRow, Column, ConnectOptions, Connection, Executor,
+    IsolationLevel, Row, SqliteConnection, SqlitePool, Statement, TransactionOptions, TypeInfo,
 };
 use sqlx_test::new;
 
@@ -368,6 +369,81 @@ SELECT id, other FROM users WHERE id = last_insert_rowid();
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_can_begin_with_transaction_options() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE savepoints (id INTEGER PRIMARY KEY)")
+        .await?;
+
+    let mut tx = conn
+        .begin_with(TransactionOptions::new().isolation_level(IsolationLevel::Serializable))
+        .await?;
+
+    tx.execute("INSERT INTO savepoints (id) VALUES (1)").await?;
+
+    tx.savepoint("after_first").await?;
+
+    for _ in 0..2 {
+        tx.execute("INSERT INTO savepoints (id) VALUES (2)").await?;
+
+        // the savepoint is kept, so it can be rolled back to again
+        tx.rollback_to_savepoint("after_first").await?;
+    }
+
+    tx.release_savepoint("after_first").await?;
+
+    // options cannot be applied to a savepoint
+    assert!(tx
+        .begin_with(TransactionOptions::new().read_only(true))
+        .await
+        .is_err());
+
+    tx.commit().await?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM savepoints")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_classifies_constraint_errors() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.execute(
+        "CREATE TEMPORARY TABLE kinds (id INTEGER PRIMARY KEY, name TEXT NOT NULL CHECK (name != ''))",
+    )
+    .await?;
+
+    conn.execute("INSERT INTO kinds (id, name) VALUES (1, 'a')")
+        .await?;
+
+    for (query, kind) in [
+        (
+            "INSERT INTO kinds (id, name) VALUES (1, 'b')",
+            ErrorKind::UniqueViolation,
+        ),
+        (
+            "INSERT INTO kinds (id, name) VALUES (2, NULL)",
+            ErrorKind::NotNullViolation,
+        ),
+        (
+            "INSERT INTO kinds (id, name) VALUES (2, '')",
+            ErrorKind::CheckViolation,
+        ),
+    ] {
+        let err = conn.execute(query).await.unwrap_err();
+
+        assert_eq!(err.as_database_error().unwrap().kind(), kind, "{}", query);
+    }
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_interleaves_reads_and_writes() -> anyhow::Result<()> {
     let mut conn = new::<Sqlite>().await?;